@@ -1,3 +1,55 @@
+// App commands get `allow-<command>` permissions, which capabilities/default.json grants to the main
+// window only. Keep this list in sync with `generate_handler!` in src/lib.rs.
+const COMMANDS: &[&str] = &[
+    "greet",
+    "check_executable_exists",
+    "get_process_status",
+    "start_node",
+    "stop_node",
+    "check_and_download_update",
+    "get_logs",
+    "clear_logs",
+    "get_current_data_path",
+    "set_custom_data_path",
+    "get_resource_history",
+    "add_content",
+    "get_content",
+    "list_objects",
+    "pin_object",
+    "unpin_object",
+    "list_peers",
+    "add_path",
+    "fetch_by_hash",
+    "connect_peer",
+    "disconnect_peer",
+    "get_bootstrap_peers",
+    "add_bootstrap_peer",
+    "remove_bootstrap_peer",
+    "get_settings",
+    "update_settings",
+    "take_settings_error",
+    "export_profile",
+    "import_profile",
+    "cancel_transfer",
+    "get_overrides",
+    "migrate_data_dir",
+    "data_dir_needs_migration",
+    "get_storage_paths",
+    "backup_node",
+    "restore_node",
+    "get_disk_usage",
+    "prepare_reset",
+    "reset_node",
+    "enable_launch_at_login",
+    "disable_launch_at_login",
+    "get_launch_at_login",
+    "open_webview",
+    "take_deep_link",
+];
+
 fn main() {
-    tauri_build::build()
+    tauri_build::try_build(
+        tauri_build::Attributes::new().app_manifest(tauri_build::AppManifest::new().commands(COMMANDS)),
+    )
+    .expect("failed to run tauri-build");
 }
@@ -0,0 +1,356 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager, State};
+
+use crate::download;
+use crate::fsutil::{collect_files, sha256_file};
+use crate::node;
+use crate::paths::{self, StoragePaths, NODE_ID};
+use crate::settings::{load_settings, AppSettings};
+use crate::{add_log_entry, start_node_from_settings, stop_node, AppState};
+
+const MANIFEST_NAME: &str = "manifest.json";
+const MANIFEST_VERSION: u32 = 1;
+const ARCHIVE_EXTENSION: &str = ".tar.zst";
+
+// How often the backup schedule is checked
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ManifestFile {
+    pub path: String,
+    pub size: u64,
+    pub sha256: String,
+}
+
+// Description of a backup archive, stored as its first entry
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupManifest {
+    pub version: u32,
+    pub node_id: String,
+    pub created_at: String,
+    pub app_version: String,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Serialize, Clone)]
+pub struct BackupResult {
+    pub archive: PathBuf,
+    pub sha256: String,
+    pub manifest: BackupManifest,
+}
+
+// Path of the checksum file written next to an archive
+fn checksum_path(archive: &Path) -> PathBuf {
+    let mut name = archive.file_name().unwrap_or_default().to_os_string();
+    name.push(".sha256");
+    archive.with_file_name(name)
+}
+
+fn archive_name() -> String {
+    format!("openhash-{}-{}{}", NODE_ID, chrono::Utc::now().format("%Y%m%d-%H%M%S"), ARCHIVE_EXTENSION)
+}
+
+// Write the DB directory and its manifest into a tar.zst archive
+fn write_archive(db_dir: &Path, archive: &Path) -> Result<BackupManifest, String> {
+    let mut files = Vec::new();
+    collect_files(db_dir, db_dir, &mut files).map_err(|e| format!("Failed to read {:?}: {}", db_dir, e))?;
+
+    let mut manifest_files = Vec::with_capacity(files.len());
+    for file in &files {
+        let sha256 = sha256_file(&file.path).map_err(|e| format!("Failed to read {:?}: {}", file.path, e))?;
+        manifest_files.push(ManifestFile {
+            path: file.relative.clone(),
+            size: file.size,
+            sha256,
+        });
+    }
+    let manifest = BackupManifest {
+        version: MANIFEST_VERSION,
+        node_id: NODE_ID.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        files: manifest_files,
+    };
+    let manifest_json = serde_json::to_vec_pretty(&manifest).map_err(|e| format!("Failed to serialize manifest: {}", e))?;
+
+    let write = || -> io::Result<()> {
+        let encoder = zstd::stream::write::Encoder::new(File::create(archive)?, 3)?;
+        let mut builder = tar::Builder::new(encoder);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(chrono::Utc::now().timestamp().max(0) as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, MANIFEST_NAME, manifest_json.as_slice())?;
+        for file in &files {
+            builder.append_path_with_name(&file.path, format!("db/{}", file.relative))?;
+        }
+        builder.into_inner()?.finish()?.sync_all()
+    };
+    write().map_err(|e| format!("Failed to write archive {:?}: {}", archive, e))?;
+    Ok(manifest)
+}
+
+// Extract and verify an archive into `target`, returning its manifest
+fn extract_archive(archive: &Path, target: &Path) -> Result<BackupManifest, String> {
+    let invalid = |reason: String| format!("Invalid backup archive {:?}: {}", archive, reason);
+    let file = File::open(archive).map_err(|e| format!("Failed to open {:?}: {}", archive, e))?;
+    let decoder = zstd::stream::read::Decoder::new(file).map_err(|e| invalid(e.to_string()))?;
+    let mut tar = tar::Archive::new(decoder);
+
+    let mut manifest: Option<BackupManifest> = None;
+    for entry in tar.entries().map_err(|e| invalid(e.to_string()))? {
+        let mut entry = entry.map_err(|e| invalid(e.to_string()))?;
+        let path = entry.path().map_err(|e| invalid(e.to_string()))?.to_string_lossy().into_owned();
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            return Err(invalid(format!("unsupported entry type for {:?}", path)));
+        }
+
+        if path == MANIFEST_NAME {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).map_err(|e| invalid(e.to_string()))?;
+            manifest = Some(serde_json::from_str(&content).map_err(|e| invalid(format!("bad manifest: {}", e)))?);
+        } else if path.starts_with("db/") {
+            // unpack_in refuses paths that would escape the target directory
+            if !entry.unpack_in(target).map_err(|e| invalid(e.to_string()))? {
+                return Err(invalid(format!("unsafe path {:?}", path)));
+            }
+        } else {
+            return Err(invalid(format!("unexpected entry {:?}", path)));
+        }
+    }
+
+    let manifest = manifest.ok_or_else(|| invalid("missing manifest".to_string()))?;
+    if manifest.version != MANIFEST_VERSION || manifest.node_id != NODE_ID {
+        return Err(invalid(format!(
+            "unsupported manifest version {} for node {:?}",
+            manifest.version, manifest.node_id
+        )));
+    }
+
+    let db_root = target.join("db");
+    let mut extracted = Vec::new();
+    if db_root.exists() {
+        collect_files(&db_root, &db_root, &mut extracted).map_err(|e| invalid(e.to_string()))?;
+    }
+    if extracted.len() != manifest.files.len() {
+        return Err(invalid("file list does not match the manifest".to_string()));
+    }
+    for file in &manifest.files {
+        let path = db_root.join(&file.path);
+        let size = fs::metadata(&path).map(|m| m.len()).map_err(|_| invalid(format!("missing {:?}", file.path)))?;
+        if size != file.size || sha256_file(&path).map_err(|e| invalid(e.to_string()))? != file.sha256 {
+            return Err(invalid(format!("checksum mismatch for {:?}", file.path)));
+        }
+    }
+    Ok(manifest)
+}
+
+// Stop the node if it runs, run `task`, then start the node again
+async fn with_node_stopped<T, F, Fut>(app_handle: &AppHandle, task: F) -> Result<T, String>
+where
+    F: FnOnce() -> Fut,
+    Fut: std::future::Future<Output = Result<T, String>>,
+{
+    let state = app_handle.state::<AppState>();
+    let was_running = *state.is_running.lock().unwrap();
+    node::check_not_running_outside(&StoragePaths::load()?, was_running)?;
+    if was_running {
+        stop_node(state.clone()).await?;
+    }
+    let result = task().await;
+    if was_running {
+        if let Err(e) = start_node_from_settings(app_handle.clone()).await {
+            add_log_entry(&state.logs, &format!("Failed to restart node: {}", e));
+        }
+    }
+    result
+}
+
+// Create a backup archive at `dest`, a .tar.zst file or a directory to place it in
+pub async fn create_backup(app_handle: &AppHandle, dest: &Path) -> Result<BackupResult, String> {
+    let db_dir = StoragePaths::load()?.db_dir.join(NODE_ID);
+    if !db_dir.exists() {
+        return Err(format!("Node database {:?} does not exist", db_dir));
+    }
+    let archive = if dest.is_dir() {
+        dest.join(archive_name())
+    } else if dest.to_string_lossy().ends_with(ARCHIVE_EXTENSION) {
+        dest.to_path_buf()
+    } else {
+        return Err(format!("Backup destination must be a directory or a {} file", ARCHIVE_EXTENSION));
+    };
+    if let Some(parent) = archive.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+    }
+
+    let state = app_handle.state::<AppState>();
+    add_log_entry(&state.logs, &format!("Backing up {:?} to {:?}", db_dir, archive));
+
+    // The daemon has no snapshot API, so the DB is only archived while the node is stopped
+    let archive_path = archive.clone();
+    let manifest = with_node_stopped(app_handle, || async move {
+        tokio::task::spawn_blocking(move || write_archive(&db_dir, &archive_path))
+            .await
+            .map_err(|e| format!("Backup task failed: {}", e))?
+    })
+    .await
+    .inspect_err(|_| {
+        let _ = fs::remove_file(&archive);
+    })?;
+
+    let sha256 = download::sha256_file(&archive).await?;
+    let file_name = archive.file_name().unwrap_or_default().to_string_lossy().into_owned();
+    fs::write(checksum_path(&archive), format!("{}  {}\n", sha256, file_name))
+        .map_err(|e| format!("Failed to write checksum file: {}", e))?;
+
+    add_log_entry(&state.logs, &format!("Backup completed: {:?} ({} files)", archive, manifest.files.len()));
+    Ok(BackupResult { archive, sha256, manifest })
+}
+
+// Back up the node database to a .tar.zst archive with a manifest and checksum
+#[tauri::command]
+pub async fn backup_node(app_handle: AppHandle, dest: String) -> Result<BackupResult, String> {
+    create_backup(&app_handle, Path::new(&dest)).await
+}
+
+// Replace the node database with the contents of a verified backup archive
+#[tauri::command]
+pub async fn restore_node(app_handle: AppHandle, state: State<'_, AppState>, archive: String) -> Result<BackupManifest, String> {
+    let archive = PathBuf::from(archive);
+
+    // Check the archive against its checksum file when there is one
+    let checksum_file = checksum_path(&archive);
+    if checksum_file.exists() {
+        let expected = fs::read_to_string(&checksum_file).map_err(|e| format!("Failed to read checksum file: {}", e))?;
+        let expected = expected.split_whitespace().next().unwrap_or_default().to_string();
+        let actual = download::sha256_file(&archive).await?;
+        if !actual.eq_ignore_ascii_case(&expected) {
+            return Err(format!("Backup archive {:?} does not match its checksum", archive));
+        }
+    }
+
+    let db_dir = StoragePaths::load()?.db_dir;
+    fs::create_dir_all(&db_dir).map_err(|e| format!("Failed to create {:?}: {}", db_dir, e))?;
+    let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let staging_dir = db_dir.join(format!(".restore-{}", timestamp));
+
+    // Extract and verify everything before the current database is touched
+    let (archive_path, staging) = (archive.clone(), staging_dir.clone());
+    let manifest = tokio::task::spawn_blocking(move || extract_archive(&archive_path, &staging))
+        .await
+        .map_err(|e| format!("Restore task failed: {}", e))?
+        .inspect_err(|_| {
+            let _ = fs::remove_dir_all(&staging_dir);
+        })?;
+    add_log_entry(&state.logs, &format!("Verified backup {:?} from {}", archive, manifest.created_at));
+
+    let node_dir = db_dir.join(NODE_ID);
+    let previous_dir = db_dir.join(format!("{}.before-restore-{}", NODE_ID, timestamp));
+    let restored = staging_dir.join("db");
+    let result = with_node_stopped(&app_handle, || async {
+        if node_dir.exists() {
+            fs::rename(&node_dir, &previous_dir).map_err(|e| format!("Failed to move current database aside: {}", e))?;
+        }
+        let moved = if restored.exists() {
+            fs::rename(&restored, &node_dir)
+        } else {
+            fs::create_dir_all(&node_dir)
+        };
+        if let Err(e) = moved {
+            // Put the previous database back
+            let _ = fs::rename(&previous_dir, &node_dir);
+            return Err(format!("Failed to move restored database into place: {}", e));
+        }
+        Ok(())
+    })
+    .await;
+
+    let _ = fs::remove_dir_all(&staging_dir);
+    result?;
+    let _ = fs::remove_dir_all(&previous_dir);
+    add_log_entry(&state.logs, &format!("Restored node database from {:?}", archive));
+    Ok(manifest)
+}
+
+// Directory for backups made by the app itself
+pub fn backups_dir(settings: &AppSettings) -> PathBuf {
+    settings
+        .backup
+        .dir
+        .as_deref()
+        .filter(|d| !d.is_empty())
+        .map(PathBuf::from)
+        .unwrap_or_else(paths::default_backups_dir)
+}
+
+// Delete the oldest scheduled backups beyond `keep`
+fn prune_backups(dir: &Path, keep: usize) -> io::Result<()> {
+    let prefix = format!("openhash-{}-", NODE_ID);
+    let mut archives: Vec<PathBuf> = fs::read_dir(dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            name.starts_with(&prefix) && name.ends_with(ARCHIVE_EXTENSION)
+        })
+        .collect();
+    // Archive names embed the creation time, so they sort chronologically
+    archives.sort();
+    let excess = archives.len().saturating_sub(keep);
+    for archive in &archives[..excess] {
+        fs::remove_file(archive)?;
+        let _ = fs::remove_file(checksum_path(archive));
+    }
+    Ok(())
+}
+
+// Age of the newest archive in `dir`, if any
+fn newest_backup_age(dir: &Path) -> Option<Duration> {
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(ARCHIVE_EXTENSION))
+        .filter_map(|entry| entry.metadata().ok()?.modified().ok()?.elapsed().ok())
+        .min()
+}
+
+// Run scheduled backups according to the backup settings
+pub fn spawn_scheduler(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SCHEDULE_CHECK_INTERVAL).await;
+
+            let Ok(settings) = load_settings() else {
+                continue;
+            };
+            if !settings.backup.enabled {
+                continue;
+            }
+            let dir = backups_dir(&settings);
+            let interval = Duration::from_secs(u64::from(settings.backup.interval_hours) * 3600);
+            if newest_backup_age(&dir).is_some_and(|age| age < interval) {
+                continue;
+            }
+
+            let state = app_handle.state::<AppState>();
+            if let Err(e) = fs::create_dir_all(&dir) {
+                add_log_entry(&state.logs, &format!("Scheduled backup failed: {}", e));
+                continue;
+            }
+            match create_backup(&app_handle, &dir).await {
+                Ok(_) => {
+                    if let Err(e) = prune_backups(&dir, settings.backup.keep as usize) {
+                        add_log_entry(&state.logs, &format!("Failed to prune old backups: {}", e));
+                    }
+                }
+                Err(e) => add_log_entry(&state.logs, &format!("Scheduled backup failed: {}", e)),
+            }
+        }
+    });
+}
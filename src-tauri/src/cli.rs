@@ -0,0 +1,404 @@
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::control::{ControlClient, ControlCommand};
+use crate::node;
+use crate::overrides::{self, Overrides};
+use crate::paths::{StoragePaths, NODE_ID};
+use crate::settings::{load_settings, AppSettings};
+use crate::supervisor::{restart_decision, RestartDecision};
+use crate::updater::{self, UpdateOutcome};
+
+pub const USAGE: &str = "Usage: openhash-desktop <command> [options]
+
+Commands:
+  start [--foreground]           Start the node in the background, or supervise it in this terminal
+  stop                           Stop the node
+  status                         Show whether the node is running (exit code 3 when stopped)
+  logs [--follow] [--lines <n>]  Print the node log, optionally waiting for new lines
+  update                         Download the latest node release
+
+The options of the desktop app (--data-dir, --api-port, ...) apply to every command.
+While the desktop app is open, the commands other than `start --foreground` are carried out by it
+and directory or port options are refused, as the app keeps the ones it was started with.";
+
+// Exit code of `status` when the node is not running, as for init scripts
+const EXIT_STOPPED: i32 = 3;
+
+// How often `logs --follow` looks for new output
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+enum Subcommand {
+    Start { foreground: bool },
+    Stop,
+    Status,
+    Logs { follow: bool, lines: usize },
+    Update,
+}
+
+// Split the subcommand and its own flags from the options shared with the desktop app
+fn parse(name: &str, args: &[String]) -> Result<(Subcommand, Vec<String>), String> {
+    let mut foreground = false;
+    let mut follow = false;
+    let mut lines = 100;
+    let mut rest = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (name, arg.as_str()) {
+            ("start", "--foreground") => foreground = true,
+            ("logs", "--follow" | "-f") => follow = true,
+            ("logs", "--lines" | "-n") => {
+                let value = args.next().ok_or_else(|| format!("Missing value for {}", arg))?;
+                lines = value
+                    .parse()
+                    .map_err(|_| format!("Invalid value {:?} for {}, expected a number of lines", value, arg))?;
+            }
+            _ => rest.push(arg.clone()),
+        }
+    }
+    let subcommand = match name {
+        "start" => Subcommand::Start { foreground },
+        "stop" => Subcommand::Stop,
+        "status" => Subcommand::Status,
+        "logs" => Subcommand::Logs { follow, lines },
+        "update" => Subcommand::Update,
+        _ => return Err(format!("Unknown command {:?}\n\n{}", name, USAGE)),
+    };
+    Ok((subcommand, rest))
+}
+
+// Run a command line subcommand without starting the desktop app.
+// Returns None when `args` don't name a subcommand, otherwise the process exit code.
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    let name = args.first()?;
+    if !matches!(name.as_str(), "start" | "stop" | "status" | "logs" | "update" | "help") {
+        return None;
+    }
+    attach_console();
+    if name == "help" {
+        println!("{}\n\n{}", USAGE, overrides::USAGE);
+        return Some(0);
+    }
+
+    let result = parse(name, &args[1..]).and_then(|(subcommand, rest)| {
+        overrides::init(Overrides::from_env_and_args(rest)?);
+        if !matches!(subcommand, Subcommand::Start { foreground: true }) {
+            if let Some(client) = ControlClient::connect() {
+                // The app keeps the directories and ports it was started with
+                if overrides::get().has_launch_options() {
+                    return Err("The desktop app is running with its own directories and ports. \
+                                Close it to use --data-dir, --bin-dir, --logs-dir, --api-port or --p2p-port \
+                                (or the matching OPENHASH_* variables)."
+                        .to_string());
+                }
+                return via_app(client, subcommand);
+            }
+        }
+        match subcommand {
+            Subcommand::Start { foreground: false } => start(),
+            Subcommand::Start { foreground: true } => start_foreground(),
+            Subcommand::Stop => stop(),
+            Subcommand::Status => status(),
+            Subcommand::Logs { follow, lines } => logs(follow, lines),
+            Subcommand::Update => update(),
+        }
+    });
+    Some(result.unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        1
+    }))
+}
+
+fn print_last_lines(content: &str, lines: usize) {
+    let all_lines: Vec<&str> = content.lines().collect();
+    for line in &all_lines[all_lines.len().saturating_sub(lines)..] {
+        println!("{}", line);
+    }
+}
+
+// Let the running desktop app do the work so both don't compete for the node and its ports
+fn via_app(mut client: ControlClient, subcommand: Subcommand) -> Result<i32, String> {
+    match subcommand {
+        Subcommand::Start { .. } => {
+            client.request(ControlCommand::Start)?;
+            println!("OpenHash node started by the desktop app");
+        }
+        Subcommand::Stop => {
+            client.request(ControlCommand::Stop)?;
+            println!("OpenHash node stopped by the desktop app");
+        }
+        Subcommand::Status => {
+            let status = client.request(ControlCommand::Status)?;
+            if !status["running"].as_bool().unwrap_or(false) {
+                println!("OpenHash node is stopped");
+                return Ok(EXIT_STOPPED);
+            }
+            println!(
+                "OpenHash node is running in the desktop app (pid {}, API port {})",
+                status["pid"], status["apiPort"]
+            );
+        }
+        Subcommand::Logs { follow, lines } => {
+            let logs = client.request(ControlCommand::Logs { follow })?;
+            print_last_lines(logs.as_str().unwrap_or_default(), lines);
+            if follow {
+                loop {
+                    let new_output = client.receive()?;
+                    let mut stdout = io::stdout().lock();
+                    let _ = stdout.write_all(new_output.as_str().unwrap_or_default().as_bytes());
+                    let _ = stdout.flush();
+                }
+            }
+        }
+        Subcommand::Update => {
+            client.request(ControlCommand::Update)?;
+            println!("OpenHash node updated by the desktop app");
+        }
+    }
+    Ok(0)
+}
+
+// Release builds have no console of their own on Windows, so print to the calling terminal
+#[cfg(windows)]
+fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+fn attach_console() {}
+
+// Everything needed to launch the node the way `start_node` does in the app
+struct Launch {
+    settings: AppSettings,
+    storage_paths: StoragePaths,
+    api_port: u16,
+    p2p_port: u16,
+}
+
+impl Launch {
+    fn load() -> Result<Self, String> {
+        let settings = load_settings()?;
+        let storage_paths = StoragePaths::resolve(&settings);
+        let overrides = overrides::get();
+        Ok(Self {
+            api_port: overrides.api_port.unwrap_or(settings.node.api_port),
+            p2p_port: overrides.p2p_port.unwrap_or(settings.node.p2p_port),
+            settings,
+            storage_paths,
+        })
+    }
+
+    // Check the node can start and spawn it with its output sent to `stdout` and `stderr`
+    fn spawn(&self, stdout: Stdio, stderr: Stdio, detach: bool) -> Result<Child, String> {
+        if let Some(pid) = node::running_pid(&self.storage_paths) {
+            return Err(format!("Node is already running (pid {})", pid));
+        }
+        let executable_path = self.storage_paths.executable_path();
+        if !executable_path.exists() {
+            return Err("OpenHash executable not found. Run `openhash-desktop update` first.".to_string());
+        }
+        let db_path = self.storage_paths.db_dir.join(NODE_ID);
+        fs::create_dir_all(&db_path).map_err(|e| format!("Failed to create DB directory: {}", e))?;
+        node::check_ports_free(self.api_port, self.p2p_port)?;
+
+        let mut cmd = node::node_command(
+            &executable_path,
+            &db_path,
+            self.api_port,
+            self.p2p_port,
+            &self.settings.bootstrap_peers,
+        );
+        cmd.stdin(Stdio::null()).stdout(stdout).stderr(stderr);
+
+        // Keep a background node out of the terminal's process group so it outlives the shell
+        #[cfg(unix)]
+        if detach {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        #[cfg(not(unix))]
+        let _ = detach;
+
+        let child = cmd.spawn().map_err(|e| format!("Failed to start process: {}", e))?;
+        node::write_pid_file(&self.storage_paths, child.id())?;
+        Ok(child)
+    }
+
+    fn open_log_file(&self) -> Result<File, String> {
+        fs::create_dir_all(&self.storage_paths.logs_dir)
+            .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+        let log_file_path = node::log_file_path(&self.storage_paths);
+        fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&log_file_path)
+            .map_err(|e| format!("Failed to open log file {:?}: {}", log_file_path, e))
+    }
+}
+
+// Start the node in the background, its output goes to the node log file
+fn start() -> Result<i32, String> {
+    let launch = Launch::load()?;
+    let log_file = launch.open_log_file()?;
+    let stderr = log_file.try_clone().map_err(|e| format!("Failed to open log file: {}", e))?;
+    let child = launch.spawn(log_file.into(), stderr.into(), true)?;
+    println!(
+        "OpenHash node started (pid {}, API port {}, P2P port {})",
+        child.id(),
+        launch.api_port,
+        launch.p2p_port
+    );
+    Ok(0)
+}
+
+// Copy the node output to this terminal and the node log file, prefixed like in the app
+fn forward_output<R: Read + Send + 'static>(reader: R, prefix: &'static str, log_file: Arc<Mutex<File>>) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        for line in BufReader::new(reader).lines() {
+            let Ok(line) = line else { break };
+            println!("{}", line);
+            let _ = writeln!(log_file.lock().unwrap(), "{}: {}", prefix, line);
+        }
+    })
+}
+
+// Run the node in this terminal and restart it according to the restart policy
+fn start_foreground() -> Result<i32, String> {
+    let launch = Launch::load()?;
+    let log_file = Arc::new(Mutex::new(launch.open_log_file()?));
+    let policy = &launch.settings.restart_policy;
+    let mut attempts = 0;
+    loop {
+        let mut child = launch.spawn(Stdio::piped(), Stdio::piped(), false)?;
+        let started = Instant::now();
+        eprintln!("OpenHash node started (pid {})", child.id());
+        let readers = [
+            child.stdout.take().map(|stdout| forward_output(stdout, "STDOUT", Arc::clone(&log_file))),
+            child.stderr.take().map(|stderr| forward_output(stderr, "STDERR", Arc::clone(&log_file))),
+        ];
+        let status = child.wait().map_err(|e| format!("Failed to wait for the node: {}", e))?;
+        node::remove_pid_file(&launch.storage_paths);
+        for reader in readers.into_iter().flatten() {
+            let _ = reader.join();
+        }
+        eprintln!("OpenHash node exited ({})", status);
+
+        match restart_decision(policy, status, started.elapsed(), &mut attempts) {
+            RestartDecision::Restart(attempt) => {
+                eprintln!(
+                    "Restarting node in {}s (attempt {}/{})",
+                    policy.delay_secs, attempt, policy.max_retries
+                );
+                thread::sleep(Duration::from_secs(policy.delay_secs));
+            }
+            RestartDecision::GiveUp(attempts) => {
+                eprintln!("Not restarting node after {} attempts", attempts);
+                return Ok(1);
+            }
+            RestartDecision::Stay => return Ok(status.code().unwrap_or(1)),
+        }
+    }
+}
+
+fn stop() -> Result<i32, String> {
+    let storage_paths = StoragePaths::load()?;
+    let Some(pid) = node::running_pid(&storage_paths) else {
+        println!("OpenHash node is not running");
+        return Ok(0);
+    };
+    node::terminate(pid)?;
+    node::remove_pid_file(&storage_paths);
+    println!("OpenHash node stopped (pid {})", pid);
+    Ok(0)
+}
+
+fn status() -> Result<i32, String> {
+    let launch = Launch::load()?;
+    match node::running_pid(&launch.storage_paths) {
+        Some(pid) => {
+            println!("OpenHash node is running (pid {}, API port {})", pid, launch.api_port);
+            Ok(0)
+        }
+        None => {
+            println!("OpenHash node is stopped");
+            Ok(EXIT_STOPPED)
+        }
+    }
+}
+
+// Print the last `lines` lines of the node log, then keep printing new output if `follow` is set
+fn logs(follow: bool, lines: usize) -> Result<i32, String> {
+    let log_file_path = node::log_file_path(&StoragePaths::load()?);
+    let content = match fs::read_to_string(&log_file_path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound && follow => String::new(),
+        Err(e) => return Err(format!("Failed to read {:?}: {}", log_file_path, e)),
+    };
+    print_last_lines(&content, lines);
+    if !follow {
+        return Ok(0);
+    }
+
+    let mut position = content.len() as u64;
+    loop {
+        thread::sleep(FOLLOW_INTERVAL);
+        let Ok(mut file) = File::open(&log_file_path) else { continue };
+        let len = file.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+        // Start over when the log was cleared or replaced
+        if len < position {
+            position = 0;
+        }
+        if len == position {
+            continue;
+        }
+        let mut new_output = Vec::new();
+        file.seek(SeekFrom::Start(position))
+            .and_then(|_| file.read_to_end(&mut new_output))
+            .map_err(|e| format!("Failed to read {:?}: {}", log_file_path, e))?;
+        position += new_output.len() as u64;
+        let mut stdout = io::stdout().lock();
+        let _ = stdout.write_all(&new_output);
+        let _ = stdout.flush();
+    }
+}
+
+fn update() -> Result<i32, String> {
+    let storage_paths = StoragePaths::load()?;
+    if let Some(pid) = node::running_pid(&storage_paths) {
+        return Err(format!("Stop the node (pid {}) before updating it", pid));
+    }
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| format!("Failed to start runtime: {}", e))?;
+    let mut last_percent = None;
+    let outcome = runtime.block_on(updater::update_executable(
+        &storage_paths,
+        &|message: &str| eprintln!("{}", message),
+        |tag| eprintln!("Downloading {}", tag),
+        |current, total| {
+            if let Some(percent) = (current * 100).checked_div(total) {
+                if last_percent != Some(percent) {
+                    last_percent = Some(percent);
+                    eprint!("\r{}%", percent);
+                    if current >= total {
+                        eprintln!();
+                    }
+                }
+            }
+            Ok(())
+        },
+    ))?;
+    match outcome {
+        UpdateOutcome::UpToDate(tag) => println!("OpenHash node is up to date ({})", tag),
+        UpdateOutcome::Installed(tag) => println!("Installed OpenHash node {}", tag),
+    }
+    Ok(0)
+}
@@ -0,0 +1,296 @@
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+use crate::AppState;
+
+// Result of adding content to the node
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AddResponse {
+    pub hash: String,
+    pub name: String,
+    pub size: u64,
+}
+
+// An object stored by the node
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ObjectInfo {
+    pub hash: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub size: u64,
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    #[serde(default)]
+    pub pinned: bool,
+    #[serde(default)]
+    pub is_directory: bool,
+    #[serde(default)]
+    pub created_at: Option<String>,
+}
+
+// An entry of a directory object
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DirectoryEntry {
+    pub name: String,
+    pub hash: String,
+    pub size: u64,
+    #[serde(default)]
+    pub is_directory: bool,
+}
+
+// A peer the node is connected to
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PeerInfo {
+    pub peer_id: String,
+    #[serde(default)]
+    pub addresses: Vec<String>,
+    #[serde(default)]
+    pub latency_ms: Option<u64>,
+}
+
+// Typed client for the REST API exposed by `openhash daemon`
+#[derive(Clone)]
+pub struct OpenHashClient {
+    base_url: String,
+    http: reqwest::Client,
+}
+
+impl OpenHashClient {
+    pub fn new(api_port: u16) -> Self {
+        Self {
+            base_url: format!("http://127.0.0.1:{}", api_port),
+            http: reqwest::Client::new(),
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    // URL of an endpoint taking a hash, which is checked so it can't reach other endpoints
+    fn object_url(&self, endpoint: &str, hash: &str) -> Result<String, String> {
+        validate_hash(hash)?;
+        Ok(self.url(&format!("/{}/{}", endpoint, hash)))
+    }
+
+    // Add a blob of content under the given file name
+    pub async fn add_content(&self, name: &str, data: Vec<u8>) -> Result<AddResponse, String> {
+        let part = reqwest::multipart::Part::bytes(data).file_name(name.to_string());
+        self.upload(reqwest::multipart::Form::new().part("file", part), false).await
+    }
+
+    // Upload a multipart form of files, either as a single file or as one directory
+    pub async fn upload(&self, form: reqwest::multipart::Form, directory: bool) -> Result<AddResponse, String> {
+        let path = if directory { "/upload/directory" } else { "/upload" };
+        let response = self
+            .http
+            .post(self.url(path))
+            .multipart(form)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to upload content: {}", e))?;
+        parse_json(response, "upload content").await
+    }
+
+    // Start a request for the raw content of an object
+    pub fn content_request(&self, hash: &str) -> Result<reqwest::RequestBuilder, String> {
+        Ok(self.http.get(self.object_url("download", hash)?))
+    }
+
+    // Get the raw content of an object
+    pub async fn get_content(&self, hash: &str) -> Result<Vec<u8>, String> {
+        let response = self
+            .content_request(hash)?
+            .send()
+            .await
+            .map_err(|e| format!("Failed to fetch content: {}", e))?;
+        let response = check_status(response, "fetch content").await?;
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| format!("Failed to read content: {}", e))?;
+        Ok(bytes.to_vec())
+    }
+
+    // Get the metadata of a single object
+    pub async fn object_info(&self, hash: &str) -> Result<ObjectInfo, String> {
+        let response = self
+            .http
+            .get(self.object_url("info", hash)?)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to get object info: {}", e))?;
+        parse_json(response, "get object info").await
+    }
+
+    // List the entries of a directory object
+    pub async fn directory_entries(&self, hash: &str) -> Result<Vec<DirectoryEntry>, String> {
+        let response = self
+            .http
+            .get(self.object_url("directory", hash)?)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list directory: {}", e))?;
+        parse_json(response, "list directory").await
+    }
+
+    // List the objects stored by the node
+    pub async fn list_objects(&self) -> Result<Vec<ObjectInfo>, String> {
+        let response = self
+            .http
+            .get(self.url("/list"))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list objects: {}", e))?;
+        parse_json(response, "list objects").await
+    }
+
+    // Pin an object so it is never garbage collected
+    pub async fn pin(&self, hash: &str) -> Result<(), String> {
+        let response = self
+            .http
+            .post(self.object_url("pin", hash)?)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to pin object: {}", e))?;
+        check_status(response, "pin object").await.map(|_| ())
+    }
+
+    // Remove the pin from an object
+    pub async fn unpin(&self, hash: &str) -> Result<(), String> {
+        let response = self
+            .http
+            .post(self.object_url("unpin", hash)?)
+            .send()
+            .await
+            .map_err(|e| format!("Failed to unpin object: {}", e))?;
+        check_status(response, "unpin object").await.map(|_| ())
+    }
+
+    // List the peers the node is connected to
+    pub async fn peers(&self) -> Result<Vec<PeerInfo>, String> {
+        let response = self
+            .http
+            .get(self.url("/peers"))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to list peers: {}", e))?;
+        parse_json(response, "list peers").await
+    }
+
+    // Dial a peer by multiaddr
+    pub async fn connect_peer(&self, address: &str) -> Result<(), String> {
+        let response = self
+            .http
+            .post(self.url("/peers/connect"))
+            .json(&serde_json::json!({ "address": address }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to connect to peer: {}", e))?;
+        check_status(response, "connect to peer").await.map(|_| ())
+    }
+
+    // Close the connection to a peer by multiaddr
+    pub async fn disconnect_peer(&self, address: &str) -> Result<(), String> {
+        let response = self
+            .http
+            .post(self.url("/peers/disconnect"))
+            .json(&serde_json::json!({ "address": address }))
+            .send()
+            .await
+            .map_err(|e| format!("Failed to disconnect from peer: {}", e))?;
+        check_status(response, "disconnect from peer").await.map(|_| ())
+    }
+
+    // Whether the API port serves an HTML interface at its root
+    pub async fn serves_html(&self) -> bool {
+        let Ok(response) = self.http.get(self.url("/")).send().await else {
+            return false;
+        };
+        response.status().is_success()
+            && response
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("text/html"))
+    }
+}
+
+// Check that a hash is safe to embed in an API path
+pub fn validate_hash(hash: &str) -> Result<(), String> {
+    if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(format!("Invalid content hash: {:?}", hash));
+    }
+    Ok(())
+}
+
+// Turn a non-success response into an error carrying the daemon's message
+pub async fn check_status(response: reqwest::Response, action: &str) -> Result<reqwest::Response, String> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await.unwrap_or_default();
+    if body.is_empty() {
+        Err(format!("Failed to {}: Status {}", action, status))
+    } else {
+        Err(format!("Failed to {}: Status {}: {}", action, status, body.trim()))
+    }
+}
+
+async fn parse_json<T: serde::de::DeserializeOwned>(response: reqwest::Response, action: &str) -> Result<T, String> {
+    check_status(response, action)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse response to {}: {}", action, e))
+}
+
+// Build a client for the node started by this app
+pub fn client_for(state: &AppState) -> Result<OpenHashClient, String> {
+    if !*state.is_running.lock().unwrap() {
+        return Err("Node is not running".to_string());
+    }
+    let api_port = state
+        .api_port
+        .lock()
+        .unwrap()
+        .ok_or_else(|| "Node API port is unknown".to_string())?;
+    Ok(OpenHashClient::new(api_port))
+}
+
+// Add content to the node
+#[tauri::command]
+pub async fn add_content(name: String, data: Vec<u8>, state: State<'_, AppState>) -> Result<AddResponse, String> {
+    client_for(&state)?.add_content(&name, data).await
+}
+
+// Get the raw content of an object by hash
+#[tauri::command]
+pub async fn get_content(hash: String, state: State<'_, AppState>) -> Result<Vec<u8>, String> {
+    client_for(&state)?.get_content(&hash).await
+}
+
+// List the objects stored by the node
+#[tauri::command]
+pub async fn list_objects(state: State<'_, AppState>) -> Result<Vec<ObjectInfo>, String> {
+    client_for(&state)?.list_objects().await
+}
+
+// Pin an object by hash
+#[tauri::command]
+pub async fn pin_object(hash: String, state: State<'_, AppState>) -> Result<(), String> {
+    client_for(&state)?.pin(&hash).await
+}
+
+// Unpin an object by hash
+#[tauri::command]
+pub async fn unpin_object(hash: String, state: State<'_, AppState>) -> Result<(), String> {
+    client_for(&state)?.unpin(&hash).await
+}
+
+// List the peers the node is connected to
+#[tauri::command]
+pub async fn list_peers(state: State<'_, AppState>) -> Result<Vec<PeerInfo>, String> {
+    client_for(&state)?.peers().await
+}
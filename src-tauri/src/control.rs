@@ -0,0 +1,335 @@
+use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::reset::random_token;
+use crate::settings::get_config_dir;
+use crate::{
+    add_log_entry, check_and_download_update, get_logs, get_process_status, start_node_from_settings, stop_node,
+    AppState,
+};
+
+// Name of the pipe the running app listens on
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\openhash-desktop-control";
+
+// How often a followed log is checked for new lines
+const FOLLOW_INTERVAL: Duration = Duration::from_millis(500);
+
+// Longest request line the app reads, far above any valid request
+const MAX_REQUEST_LINE: u64 = 16 * 1024;
+
+// Longest response line the command line reads; responses carry the app logs
+const MAX_RESPONSE_LINE: u64 = 16 * 1024 * 1024;
+
+// Operations available to local scripts, mirroring the app commands
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "snake_case")]
+pub enum ControlCommand {
+    Status,
+    Start,
+    Stop,
+    // Send the current logs, then keep sending new lines until the client disconnects
+    Logs {
+        #[serde(default)]
+        follow: bool,
+    },
+    Update,
+}
+
+// One request per line, authenticated with the token the app writes to `token_path`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ControlRequest {
+    pub token: String,
+    #[serde(flatten)]
+    pub command: ControlCommand,
+}
+
+// One response line per request, or one per new chunk of a followed log
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct ControlResponse {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ControlResponse {
+    fn from_result<T: Serialize>(result: Result<T, String>) -> Self {
+        match result.and_then(|value| serde_json::to_value(value).map_err(|e| e.to_string())) {
+            Ok(value) => Self { ok: true, result: Some(value), error: None },
+            Err(e) => Self { ok: false, result: None, error: Some(e) },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct NodeStatus {
+    running: bool,
+    pid: Option<u32>,
+    api_port: Option<u16>,
+}
+
+// File holding the token of the running app, readable by the current user only
+fn token_path() -> Result<PathBuf, String> {
+    Ok(get_config_dir()?.join("control.token"))
+}
+
+#[cfg(unix)]
+fn socket_path() -> Result<PathBuf, String> {
+    Ok(get_config_dir()?.join("control.sock"))
+}
+
+fn write_token(token: &str) -> Result<(), String> {
+    let path = token_path()?;
+    let mut options = fs::OpenOptions::new();
+    options.create(true).write(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(&path)
+        .and_then(|mut file| file.write_all(token.as_bytes()))
+        .map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+// Compare tokens without leaking the position of the first difference
+fn token_matches(expected: &str, actual: &str) -> bool {
+    expected.len() == actual.len()
+        && expected.bytes().zip(actual.bytes()).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+async fn write_response<W: AsyncWrite + Unpin>(writer: &mut W, response: &ControlResponse) -> Result<(), String> {
+    let mut line = serde_json::to_vec(response).map_err(|e| e.to_string())?;
+    line.push(b'\n');
+    writer.write_all(&line).await.map_err(|e| e.to_string())?;
+    writer.flush().await.map_err(|e| e.to_string())
+}
+
+// Keep sending the lines added to the app logs after `sent`
+async fn follow_logs<W: AsyncWrite + Unpin>(app_handle: &AppHandle, writer: &mut W, sent: String) -> Result<(), String> {
+    let state = app_handle.state::<AppState>();
+    let mut last_line = sent.lines().last().map(str::to_string);
+    loop {
+        tokio::time::sleep(FOLLOW_INTERVAL).await;
+        let logs = state.logs.lock().unwrap().clone();
+        // The logs are trimmed from the front, so find where the last sent line is now
+        let new_output = match &last_line {
+            Some(line) => match logs.rfind(line.as_str()) {
+                Some(position) => logs[position + line.len()..].trim_start_matches('\n'),
+                None => logs.as_str(),
+            },
+            None => logs.as_str(),
+        };
+        if new_output.is_empty() {
+            continue;
+        }
+        write_response(writer, &ControlResponse::from_result(Ok(new_output))).await?;
+        last_line = logs.lines().last().map(str::to_string);
+    }
+}
+
+async fn handle(app_handle: &AppHandle, command: ControlCommand) -> ControlResponse {
+    let state = app_handle.state::<AppState>();
+    match command {
+        ControlCommand::Status => {
+            let status = get_process_status(app_handle.state()).await.map(|running| NodeStatus {
+                running,
+                pid: state.process.lock().unwrap().as_ref().map(|child| child.id()),
+                api_port: *state.api_port.lock().unwrap(),
+            });
+            ControlResponse::from_result(status)
+        }
+        ControlCommand::Start => ControlResponse::from_result(start_node_from_settings(app_handle.clone()).await),
+        ControlCommand::Stop => ControlResponse::from_result(stop_node(app_handle.state()).await),
+        ControlCommand::Logs { .. } => ControlResponse::from_result(get_logs(app_handle.state()).await),
+        ControlCommand::Update => {
+            ControlResponse::from_result(check_and_download_update(app_handle.clone(), app_handle.state()).await)
+        }
+    }
+}
+
+// Read one request line, refusing lines longer than `MAX_REQUEST_LINE` before buffering them.
+// Returns None when the client closed the connection.
+async fn read_request_line<R: AsyncBufRead + Unpin>(reader: &mut R) -> Result<Option<String>, String> {
+    let mut line = String::new();
+    let read = reader
+        .take(MAX_REQUEST_LINE + 1)
+        .read_line(&mut line)
+        .await
+        .map_err(|e| format!("Failed to read request: {}", e))?;
+    if read == 0 {
+        return Ok(None);
+    }
+    if read as u64 > MAX_REQUEST_LINE {
+        return Err(format!("Request is longer than {} bytes", MAX_REQUEST_LINE));
+    }
+    Ok(Some(line))
+}
+
+// Answer the requests of one client until it disconnects
+async fn serve_connection<S: AsyncRead + AsyncWrite + Unpin>(app_handle: AppHandle, stream: S, token: String) {
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = tokio::io::BufReader::new(reader);
+    loop {
+        let line = match read_request_line(&mut reader).await {
+            Ok(Some(line)) => line,
+            Ok(None) => return,
+            Err(e) => {
+                let _ = write_response(&mut writer, &ControlResponse::from_result::<()>(Err(e))).await;
+                return;
+            }
+        };
+        let request = match serde_json::from_str::<ControlRequest>(&line) {
+            Ok(request) => request,
+            Err(e) => {
+                let response = ControlResponse::from_result::<()>(Err(format!("Invalid request: {}", e)));
+                if write_response(&mut writer, &response).await.is_err() {
+                    return;
+                }
+                continue;
+            }
+        };
+        if !token_matches(&token, &request.token) {
+            let state = app_handle.state::<AppState>();
+            add_log_entry(&state.logs, "Rejected control request with an invalid token");
+            let _ = write_response(&mut writer, &ControlResponse::from_result::<()>(Err("Invalid token".to_string()))).await;
+            return;
+        }
+
+        let follow = matches!(request.command, ControlCommand::Logs { follow: true });
+        let response = handle(&app_handle, request.command).await;
+        if write_response(&mut writer, &response).await.is_err() {
+            return;
+        }
+        if follow {
+            let sent = response.result.as_ref().and_then(Value::as_str).unwrap_or_default().to_string();
+            let _ = follow_logs(&app_handle, &mut writer, sent).await;
+            return;
+        }
+    }
+}
+
+#[cfg(unix)]
+async fn listen(app_handle: AppHandle, token: String) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixListener;
+
+    let path = socket_path()?;
+    if std::os::unix::net::UnixStream::connect(&path).is_ok() {
+        return Err(format!("Another instance is already listening on {:?}", path));
+    }
+    // Left over from an app that didn't shut down cleanly
+    let _ = fs::remove_file(&path);
+    let listener = UnixListener::bind(&path).map_err(|e| format!("Failed to listen on {:?}: {}", path, e))?;
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+        .map_err(|e| format!("Failed to restrict {:?}: {}", path, e))?;
+    loop {
+        let (stream, _) = listener.accept().await.map_err(|e| format!("Failed to accept control connection: {}", e))?;
+        tauri::async_runtime::spawn(serve_connection(app_handle.clone(), stream, token.clone()));
+    }
+}
+
+#[cfg(windows)]
+async fn listen(app_handle: AppHandle, token: String) -> Result<(), String> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let create = |first: bool| {
+        ServerOptions::new()
+            .first_pipe_instance(first)
+            .reject_remote_clients(true)
+            .create(PIPE_NAME)
+            .map_err(|e| format!("Failed to create pipe {}: {}", PIPE_NAME, e))
+    };
+    let mut server = create(true)?;
+    loop {
+        server.connect().await.map_err(|e| format!("Failed to accept control connection: {}", e))?;
+        let connected = std::mem::replace(&mut server, create(false)?);
+        tauri::async_runtime::spawn(serve_connection(app_handle.clone(), connected, token.clone()));
+    }
+}
+
+// Start the control endpoint of the running app. Failures are logged, the app works without it.
+pub fn spawn_server(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let result = match random_token().and_then(|token| write_token(&token).map(|_| token)) {
+            Ok(token) => listen(app_handle.clone(), token).await,
+            Err(e) => Err(e),
+        };
+        if let Err(e) = result {
+            let state = app_handle.state::<AppState>();
+            add_log_entry(&state.logs, &format!("Control endpoint unavailable: {}", e));
+        }
+    });
+}
+
+#[cfg(unix)]
+type Stream = std::os::unix::net::UnixStream;
+
+#[cfg(windows)]
+type Stream = std::fs::File;
+
+// Connection to the control endpoint of a running app, used by the command line
+pub struct ControlClient {
+    token: String,
+    reader: BufReader<Stream>,
+    writer: Stream,
+}
+
+impl ControlClient {
+    // Connect to the running app, or None when no app is listening
+    pub fn connect() -> Option<Self> {
+        #[cfg(unix)]
+        let stream = Stream::connect(socket_path().ok()?).ok()?;
+        #[cfg(windows)]
+        let stream = fs::OpenOptions::new().read(true).write(true).open(PIPE_NAME).ok()?;
+
+        let token = fs::read_to_string(token_path().ok()?).ok()?.trim().to_string();
+        let writer = stream.try_clone().ok()?;
+        Some(Self { token, reader: BufReader::new(stream), writer })
+    }
+
+    pub fn send(&mut self, command: ControlCommand) -> Result<(), String> {
+        let request = ControlRequest { token: self.token.clone(), command };
+        let mut line = serde_json::to_vec(&request).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        self.writer
+            .write_all(&line)
+            .and_then(|_| self.writer.flush())
+            .map_err(|e| format!("Failed to send request to the app: {}", e))
+    }
+
+    // Read the next response, turning an error response into Err
+    pub fn receive(&mut self) -> Result<Value, String> {
+        let mut line = String::new();
+        match (&mut self.reader).take(MAX_RESPONSE_LINE + 1).read_line(&mut line) {
+            Ok(0) => return Err("The app closed the connection".to_string()),
+            Ok(read) if read as u64 > MAX_RESPONSE_LINE => {
+                return Err(format!("Response from the app is longer than {} bytes", MAX_RESPONSE_LINE))
+            }
+            Ok(_) => {}
+            Err(e) => return Err(format!("Failed to read response from the app: {}", e)),
+        }
+        let response: ControlResponse =
+            serde_json::from_str(&line).map_err(|e| format!("Invalid response from the app: {}", e))?;
+        if response.ok {
+            Ok(response.result.unwrap_or(Value::Null))
+        } else {
+            Err(response.error.unwrap_or_else(|| "The app rejected the request".to_string()))
+        }
+    }
+
+    pub fn request(&mut self, command: ControlCommand) -> Result<Value, String> {
+        self.send(command)?;
+        self.receive()
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::http::{header, Request, Response, StatusCode};
+use tauri::{AppHandle, Manager, UriSchemeContext, UriSchemeResponder, Url};
+
+use crate::client::{client_for, PeerInfo};
+use crate::disk::{self, DiskUsage};
+use crate::monitor::ResourceSample;
+use crate::paths::NODE_ID;
+use crate::AppState;
+
+// Scheme of the built-in dashboard, used when the daemon has no web interface of its own
+pub const SCHEME: &str = "openhash-dashboard";
+
+// The page refreshes every few seconds, the object list is fetched again at most this often
+const OBJECTS_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+const INDEX_HTML: &str = include_str!("../dashboard/index.html");
+
+// The page only talks to its own origin and loads nothing from elsewhere
+const CONTENT_SECURITY_POLICY: &str =
+    "default-src 'none'; script-src 'unsafe-inline'; style-src 'unsafe-inline'; connect-src 'self'; img-src data:";
+
+// Address of the dashboard, following how each platform exposes custom protocols
+pub fn url() -> Url {
+    let url = if cfg!(any(windows, target_os = "android")) {
+        format!("http://{}.localhost/", SCHEME)
+    } else {
+        format!("{}://localhost/", SCHEME)
+    };
+    url.parse().expect("dashboard URL is valid")
+}
+
+#[derive(Debug, Serialize, Default, Clone)]
+#[serde(rename_all = "camelCase")]
+struct ObjectSummary {
+    count: usize,
+    pinned: usize,
+    total_bytes: u64,
+}
+
+// Last object summary and when it was taken, keyed by the API port it came from
+static OBJECTS_CACHE: Mutex<Option<(u16, Instant, ObjectSummary)>> = Mutex::new(None);
+
+// Snapshot rendered by the dashboard page
+#[derive(Debug, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct DashboardStatus {
+    node_id: String,
+    running: bool,
+    api_port: Option<u16>,
+    peers: Option<Vec<PeerInfo>>,
+    objects: Option<ObjectSummary>,
+    resources: Option<ResourceSample>,
+    disk: Option<DiskUsage>,
+    errors: Vec<String>,
+}
+
+async fn collect_status(app_handle: &AppHandle) -> DashboardStatus {
+    let state = app_handle.state::<AppState>();
+    let mut status = DashboardStatus {
+        node_id: NODE_ID.to_string(),
+        running: *state.is_running.lock().unwrap(),
+        api_port: *state.api_port.lock().unwrap(),
+        resources: state.monitor.lock().unwrap().latest(),
+        ..Default::default()
+    };
+
+    // Database sizes are shared with the resource monitor, which measures them once a minute
+    match disk::get_disk_usage().await {
+        Ok(usage) => status.disk = Some(usage),
+        Err(e) => status.errors.push(e),
+    }
+
+    if let Ok(client) = client_for(&state) {
+        match client.peers().await {
+            Ok(peers) => status.peers = Some(peers),
+            Err(e) => status.errors.push(e),
+        }
+        let api_port = status.api_port.unwrap_or_default();
+        let cached = match OBJECTS_CACHE.lock().unwrap().as_ref() {
+            Some((port, taken, summary)) if *port == api_port && taken.elapsed() < OBJECTS_REFRESH_INTERVAL => {
+                Some(summary.clone())
+            }
+            _ => None,
+        };
+        if cached.is_some() {
+            status.objects = cached;
+        } else {
+            match client.list_objects().await {
+                Ok(objects) => {
+                    let summary = ObjectSummary {
+                        count: objects.len(),
+                        pinned: objects.iter().filter(|object| object.pinned).count(),
+                        total_bytes: objects.iter().map(|object| object.size).sum(),
+                    };
+                    *OBJECTS_CACHE.lock().unwrap() = Some((api_port, Instant::now(), summary.clone()));
+                    status.objects = Some(summary);
+                }
+                Err(e) => status.errors.push(e),
+            }
+        }
+    }
+    status
+}
+
+fn response(status: StatusCode, content_type: &str, body: Vec<u8>) -> Response<Vec<u8>> {
+    Response::builder()
+        .status(status)
+        .header(header::CONTENT_TYPE, content_type)
+        .header(header::CACHE_CONTROL, "no-store")
+        .header("Content-Security-Policy", CONTENT_SECURITY_POLICY)
+        .body(body)
+        .unwrap_or_default()
+}
+
+async fn handle(app_handle: AppHandle, request: Request<Vec<u8>>) -> Response<Vec<u8>> {
+    if request.method() != tauri::http::Method::GET {
+        return response(StatusCode::METHOD_NOT_ALLOWED, "text/plain", b"Method not allowed".to_vec());
+    }
+    match request.uri().path() {
+        "/" | "/index.html" => response(StatusCode::OK, "text/html; charset=utf-8", INDEX_HTML.as_bytes().to_vec()),
+        "/status.json" => {
+            let status = collect_status(&app_handle).await;
+            match serde_json::to_vec(&status) {
+                Ok(body) => response(StatusCode::OK, "application/json", body),
+                Err(e) => response(StatusCode::INTERNAL_SERVER_ERROR, "text/plain", e.to_string().into_bytes()),
+            }
+        }
+        _ => response(StatusCode::NOT_FOUND, "text/plain", b"Not found".to_vec()),
+    }
+}
+
+// Protocol handler registered for `SCHEME`
+pub fn protocol(context: UriSchemeContext<'_, tauri::Wry>, request: Request<Vec<u8>>, responder: UriSchemeResponder) {
+    let app_handle = context.app_handle().clone();
+    tauri::async_runtime::spawn(async move {
+        responder.respond(handle(app_handle, request).await);
+    });
+}
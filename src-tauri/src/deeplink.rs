@@ -0,0 +1,204 @@
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State, Url};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use crate::client::{client_for, validate_hash};
+use crate::tray::show_main_window;
+use crate::{add_log_entry, start_node_from_settings, AppState};
+
+// Scheme of shared content links, `openhash://<hash>`
+pub const SCHEME: &str = "openhash";
+
+// Longest hash accepted from a link, well above the length of any digest the daemon uses
+const MAX_HASH_LEN: usize = 128;
+
+// How long a link waits for the node API after starting the node, and how often it checks
+const API_READY_TIMEOUT: Duration = Duration::from_secs(30);
+const API_READY_INTERVAL: Duration = Duration::from_millis(500);
+
+// What the link asks for; without an action the user picks one
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkAction {
+    Ask,
+    Fetch,
+    Pin,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLink {
+    pub hash: String,
+    pub action: LinkAction,
+}
+
+// Whether a command line argument is a link rather than an option
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.split_once(':')
+        .is_some_and(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME))
+}
+
+// Parse `openhash://<hash>` or `openhash:<hash>`, optionally with `?action=fetch` or `?action=pin`
+pub fn parse(url: &Url) -> Result<DeepLink, String> {
+    let invalid = |reason: &str| format!("Invalid {} link {:?}: {}", SCHEME, url.as_str(), reason);
+    if url.scheme() != SCHEME {
+        return Err(invalid("unexpected scheme"));
+    }
+    if !url.username().is_empty() || url.password().is_some() || url.port().is_some() || url.fragment().is_some() {
+        return Err(invalid("only a hash and an action are allowed"));
+    }
+
+    let hash = match url.host_str() {
+        Some(host) if url.path().is_empty() || url.path() == "/" => host,
+        Some(_) => return Err(invalid("unexpected path after the hash")),
+        None => url.path(),
+    };
+    if hash.len() > MAX_HASH_LEN {
+        return Err(invalid("the hash is too long"));
+    }
+    validate_hash(hash).map_err(|e| invalid(&e))?;
+
+    let mut action = LinkAction::Ask;
+    for (key, value) in url.query_pairs() {
+        action = match (key.as_ref(), value.as_ref()) {
+            ("action", "fetch") => LinkAction::Fetch,
+            ("action", "pin") => LinkAction::Pin,
+            _ => return Err(invalid(&format!("unsupported parameter {}={}", key, value))),
+        };
+    }
+    Ok(DeepLink { hash: hash.to_string(), action })
+}
+
+// Bring the app forward for an opened link, make sure the node runs and let the window offer the link
+async fn handle_link(app_handle: AppHandle, url: Url) {
+    let state = app_handle.state::<AppState>();
+    show_main_window(&app_handle);
+    let link = match parse(&url) {
+        Ok(link) => link,
+        Err(e) => {
+            add_log_entry(&state.logs, &e);
+            let _ = app_handle.emit_to("main", "deep_link_error", e);
+            return;
+        }
+    };
+    add_log_entry(&state.logs, &format!("Opened link for {}", link.hash));
+
+    let ready = async {
+        if !*state.is_running.lock().unwrap() {
+            start_node_from_settings(app_handle.clone())
+                .await
+                .map_err(|e| format!("the node could not be started: {}", e))?;
+        }
+        wait_for_api(&state).await
+    };
+    if let Err(e) = ready.await {
+        let e = format!("Cannot open the link for {}: {}", link.hash, e);
+        add_log_entry(&state.logs, &e);
+        let _ = app_handle.emit_to("main", "deep_link_error", e);
+        return;
+    }
+
+    // The window picks the link up with `take_deep_link`, also when it opens after the link arrived
+    *state.deep_link.lock().unwrap() = Some(link);
+    let _ = app_handle.emit_to("main", "deep_link", ());
+}
+
+// A freshly started node takes a moment before its API answers
+async fn wait_for_api(state: &AppState) -> Result<(), String> {
+    let started = Instant::now();
+    loop {
+        let result = match client_for(state) {
+            Ok(client) => client.peers().await.map(|_| ()),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => return Ok(()),
+            Err(e) if started.elapsed() >= API_READY_TIMEOUT => {
+                return Err(format!("the node API did not become ready: {}", e))
+            }
+            Err(_) => tokio::time::sleep(API_READY_INTERVAL).await,
+        }
+    }
+}
+
+fn handle_urls(app_handle: &AppHandle, urls: Vec<Url>) {
+    for url in urls {
+        tauri::async_runtime::spawn(handle_link(app_handle.clone(), url));
+    }
+}
+
+// Register the app for `openhash://` links and handle the link it was launched with.
+// Links opened while the app runs arrive here too, forwarded by the single instance plugin.
+pub fn setup(app_handle: &AppHandle) {
+    let deep_link = app_handle.deep_link();
+
+    // macOS registers the scheme from the bundle, the other platforms at runtime
+    #[cfg(any(windows, target_os = "linux"))]
+    if let Err(e) = deep_link.register(SCHEME) {
+        let state = app_handle.state::<AppState>();
+        add_log_entry(&state.logs, &format!("Failed to register {} links: {}", SCHEME, e));
+    }
+
+    let handle = app_handle.clone();
+    deep_link.on_open_url(move |event| handle_urls(&handle, event.urls()));
+    if let Ok(Some(urls)) = deep_link.get_current() {
+        handle_urls(app_handle, urls);
+    }
+}
+
+// Take the link waiting to be offered to the user, if any
+#[tauri::command]
+pub fn take_deep_link(state: State<'_, AppState>) -> Option<DeepLink> {
+    state.deep_link.lock().unwrap().take()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(url: &str) -> Result<DeepLink, String> {
+        parse(&url.parse().unwrap())
+    }
+
+    #[test]
+    fn parses_hash_forms() {
+        for url in ["openhash://QmHash123", "openhash://QmHash123/", "openhash:QmHash123"] {
+            let link = parse_str(url).unwrap();
+            assert_eq!(link.hash, "QmHash123", "{}", url);
+            assert_eq!(link.action, LinkAction::Ask, "{}", url);
+        }
+    }
+
+    #[test]
+    fn parses_actions() {
+        assert_eq!(parse_str("openhash://QmHash123?action=fetch").unwrap().action, LinkAction::Fetch);
+        assert_eq!(parse_str("openhash:QmHash123?action=pin").unwrap().action, LinkAction::Pin);
+    }
+
+    #[test]
+    fn rejects_invalid_links() {
+        let too_long = format!("openhash://{}", "a".repeat(MAX_HASH_LEN + 1));
+        for url in [
+            "https://QmHash123",
+            "openhash://",
+            "openhash://QmHash123/extra",
+            "openhash://user@QmHash123",
+            "openhash://QmHash123:8080",
+            "openhash://QmHash123#top",
+            "openhash:Qm%2E%2E",
+            "openhash://QmHash123?action=delete",
+            "openhash://QmHash123?other=1",
+            too_long.as_str(),
+        ] {
+            assert!(parse_str(url).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn recognizes_link_arguments() {
+        assert!(is_deep_link("openhash://QmHash123"));
+        assert!(is_deep_link("OpenHash:QmHash123"));
+        assert!(!is_deep_link("--start"));
+        assert!(!is_deep_link("https://example.org"));
+    }
+}
@@ -0,0 +1,199 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::monitor::cached_dir_size;
+use crate::node;
+use crate::notify::{self, Category};
+use crate::paths::{StoragePaths, KNOWN_NODES};
+use crate::settings::{load_settings, DiskSettings};
+use crate::{add_log_entry, stop_node, AppState};
+
+// How often the disk usage is checked in the background
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum DiskLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeDiskUsage {
+    id: String,
+    path: PathBuf,
+    size_bytes: u64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DiskUsage {
+    nodes: Vec<NodeDiskUsage>,
+    free_bytes: Option<u64>,
+    total_bytes: Option<u64>,
+    level: DiskLevel,
+    message: Option<String>,
+}
+
+// Payload of `disk_warning` events
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct DiskAlert {
+    usage: DiskUsage,
+    node_stopped: bool,
+}
+
+// The closest existing ancestor, so a volume can be queried before the DB is created
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|p| p.exists())
+}
+
+// Free and total bytes of the volume containing `path`
+#[cfg(target_os = "linux")]
+fn volume_space(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let path = std::ffi::CString::new(existing_ancestor(path)?.as_os_str().as_bytes()).ok()?;
+    let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stat) } != 0 {
+        return None;
+    }
+    let block_size = stat.f_frsize as u64;
+    Some((stat.f_bavail as u64 * block_size, stat.f_blocks as u64 * block_size))
+}
+
+// Free and total bytes of the volume containing `path`
+#[cfg(not(target_os = "linux"))]
+fn volume_space(path: &Path) -> Option<(u64, u64)> {
+    let path = std::fs::canonicalize(existing_ancestor(path)?).ok()?;
+    let disks = sysinfo::Disks::new_with_refreshed_list();
+    // The disk with the longest matching mount point holds the path
+    disks
+        .list()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().as_os_str().len())
+        .map(|disk| (disk.available_space(), disk.total_space()))
+}
+
+fn evaluate(nodes: &[NodeDiskUsage], free_bytes: Option<u64>, limits: &DiskSettings) -> (DiskLevel, Option<String>) {
+    if let Some(free) = free_bytes {
+        if free < limits.critical_free_mb * MB {
+            return (DiskLevel::Critical, Some(format!("Only {} MB of disk space left", free / MB)));
+        }
+    }
+    if let Some(quota) = limits.quota_mb {
+        if let Some(node) = nodes.iter().find(|node| node.size_bytes > quota * MB) {
+            return (
+                DiskLevel::Warning,
+                Some(format!("Data of {} uses {} MB, above the {} MB quota", node.id, node.size_bytes / MB, quota)),
+            );
+        }
+    }
+    if let Some(free) = free_bytes {
+        if free < limits.warn_free_mb * MB {
+            return (DiskLevel::Warning, Some(format!("Disk space is running low, {} MB left", free / MB)));
+        }
+    }
+    (DiskLevel::Ok, None)
+}
+
+// Measure the node directories and the volume they live on
+fn measure(limits: &DiskSettings) -> Result<DiskUsage, String> {
+    let db_dir = StoragePaths::load()?.db_dir;
+    let nodes: Vec<NodeDiskUsage> = KNOWN_NODES
+        .iter()
+        .map(|id| {
+            let path = db_dir.join(id);
+            NodeDiskUsage {
+                id: id.to_string(),
+                size_bytes: cached_dir_size(&path),
+                path,
+            }
+        })
+        .collect();
+    let space = volume_space(&db_dir);
+    let (level, message) = evaluate(&nodes, space.map(|(free, _)| free), limits);
+    Ok(DiskUsage {
+        nodes,
+        free_bytes: space.map(|(free, _)| free),
+        total_bytes: space.map(|(_, total)| total),
+        level,
+        message,
+    })
+}
+
+// Get the disk usage of each node directory and the free space on its volume
+#[tauri::command]
+pub async fn get_disk_usage() -> Result<DiskUsage, String> {
+    let limits = load_settings()?.disk;
+    tokio::task::spawn_blocking(move || measure(&limits))
+        .await
+        .map_err(|e| format!("Disk usage task failed: {}", e))?
+}
+
+// Check the disk usage periodically, emitting `disk_warning` events when the level worsens
+// and stopping the node on critically low space if configured to
+pub fn spawn_watcher(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut last_level = DiskLevel::Ok;
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let Ok(limits) = load_settings().map(|settings| settings.disk) else {
+                continue;
+            };
+            let auto_stop = limits.auto_stop;
+            let usage = match tokio::task::spawn_blocking(move || measure(&limits)).await {
+                Ok(Ok(usage)) => usage,
+                _ => continue,
+            };
+
+            let level = usage.level;
+            let state = app_handle.state::<AppState>();
+            let mut node_stopped = false;
+            if level == DiskLevel::Critical && auto_stop {
+                // A node started with `openhash-desktop start` fills the same disk
+                let outside_pid = StoragePaths::load().ok().and_then(|paths| node::running_pid(&paths));
+                let result = if *state.is_running.lock().unwrap() {
+                    add_log_entry(&state.logs, "Stopping node because the disk is almost full");
+                    Some(stop_node(state.clone()).await.map(|_| ()))
+                } else if let Some(pid) = outside_pid {
+                    add_log_entry(&state.logs, &format!("Stopping node (pid {}) because the disk is almost full", pid));
+                    Some(
+                        tokio::task::spawn_blocking(move || node::terminate(pid))
+                            .await
+                            .map_err(|e| format!("Stop task failed: {}", e))
+                            .and_then(|result| result),
+                    )
+                } else {
+                    None
+                };
+                match result {
+                    Some(Ok(())) => node_stopped = true,
+                    Some(Err(e)) => add_log_entry(&state.logs, &format!("Failed to stop node: {}", e)),
+                    None => {}
+                }
+            }
+
+            let worsened = matches!(
+                (last_level, level),
+                (DiskLevel::Ok, DiskLevel::Warning | DiskLevel::Critical) | (DiskLevel::Warning, DiskLevel::Critical)
+            );
+            if worsened || node_stopped {
+                if let Some(message) = &usage.message {
+                    add_log_entry(&state.logs, message);
+                    notify::notify(&app_handle, Category::DiskSpace, "OpenHash is running out of disk space", message);
+                }
+                let _ = app_handle.emit("disk_warning", DiskAlert { usage, node_stopped });
+            }
+            last_level = level;
+        }
+    });
+}
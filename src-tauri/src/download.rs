@@ -0,0 +1,88 @@
+use std::path::{Path, PathBuf};
+use futures_util::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use crate::fsutil;
+
+// Download a response body to `path`, resuming from `resume_from` bytes when the server supports it.
+// `known_total` may be zero if the size is not known up front. `on_progress` is called with the
+// bytes written so far and the expected total after every chunk and may abort the download.
+// Returns the final size of the file.
+pub async fn download_to_file<F>(
+    request: reqwest::RequestBuilder,
+    path: &Path,
+    resume_from: u64,
+    known_total: u64,
+    mut on_progress: F,
+) -> Result<u64, String>
+where
+    F: FnMut(u64, u64) -> Result<(), String>,
+{
+    let mut request_builder = request;
+    if resume_from > 0 {
+        request_builder = request_builder.header(reqwest::header::RANGE, format!("bytes={}-", resume_from));
+    }
+
+    let response = request_builder
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download: {}", e))?;
+
+    if !response.status().is_success() && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        return Err(format!("Failed to download: Status {}", response.status()));
+    }
+
+    // A server that ignores the range header sends the whole body again
+    let mut downloaded_bytes = if response.status() == reqwest::StatusCode::PARTIAL_CONTENT {
+        resume_from
+    } else {
+        0
+    };
+
+    let total_size = if known_total > 0 {
+        known_total
+    } else {
+        response.content_length().unwrap_or(0) + downloaded_bytes
+    };
+
+    let mut file = tokio::fs::OpenOptions::new()
+        .create(true)
+        .write(true)
+        .append(downloaded_bytes > 0) // Only append if resuming
+        .truncate(downloaded_bytes == 0)
+        .open(path)
+        .await
+        .map_err(|e| format!("Failed to open file for writing: {}", e))?;
+
+    let mut stream = response.bytes_stream();
+
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("Error while downloading chunk: {}", e))?;
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| format!("Error while writing to file: {}", e))?;
+        downloaded_bytes += chunk.len() as u64;
+        on_progress(downloaded_bytes, total_size)?;
+    }
+
+    file.flush()
+        .await
+        .map_err(|e| format!("Error while writing to file: {}", e))?;
+
+    Ok(downloaded_bytes)
+}
+
+// Path of the partial file used while downloading to `path`
+pub fn partial_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+// Compute the hex encoded SHA-256 digest of a file
+pub async fn sha256_file(path: &Path) -> Result<String, String> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || fsutil::sha256_file(&path).map_err(|e| format!("Failed to read {:?}: {}", path, e)))
+        .await
+        .map_err(|e| format!("Failed to hash file: {}", e))?
+}
@@ -0,0 +1,142 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::client::{self, OpenHashClient};
+use crate::download;
+use crate::transfer::ProgressTracker;
+use crate::{add_log_entry, AppState};
+
+// A single file of the fetched object and where it is written
+struct FetchFile {
+    hash: String,
+    dest: PathBuf,
+    size: u64,
+}
+
+// Reject directory entry names which would escape the destination directory
+fn safe_entry_name(name: &str) -> Result<&str, String> {
+    let mut components = Path::new(name).components();
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(_)), None) => Ok(name),
+        _ => Err(format!("Refusing to write directory entry with unsafe name {:?}", name)),
+    }
+}
+
+// Resolve an object into the list of files to fetch, creating directories on the way
+async fn plan_fetch(client: &OpenHashClient, hash: &str, dest: &Path) -> Result<Vec<FetchFile>, String> {
+    let info = client.object_info(hash).await?;
+    if !info.is_directory {
+        return Ok(vec![FetchFile {
+            hash: hash.to_string(),
+            dest: dest.to_path_buf(),
+            size: info.size,
+        }]);
+    }
+
+    let mut files = Vec::new();
+    let mut directories = vec![(hash.to_string(), dest.to_path_buf())];
+    while let Some((dir_hash, dir_path)) = directories.pop() {
+        fs::create_dir_all(&dir_path).map_err(|e| format!("Failed to create directory {:?}: {}", dir_path, e))?;
+        for entry in client.directory_entries(&dir_hash).await? {
+            client::validate_hash(&entry.hash)?;
+            let path = dir_path.join(safe_entry_name(&entry.name)?);
+            if entry.is_directory {
+                directories.push((entry.hash, path));
+            } else {
+                files.push(FetchFile {
+                    hash: entry.hash,
+                    dest: path,
+                    size: entry.size,
+                });
+            }
+        }
+    }
+    Ok(files)
+}
+
+// Download one file into its `.part` file, verify its hash and move it into place
+async fn fetch_file(client: &OpenHashClient, file: &FetchFile, tracker: &ProgressTracker) -> Result<(), String> {
+    let partial_path = download::partial_path(&file.dest);
+    let mut resume_from = fs::metadata(&partial_path).map(|m| m.len()).unwrap_or(0);
+    if resume_from > file.size {
+        fs::remove_file(&partial_path).map_err(|e| format!("Failed to remove stale partial file: {}", e))?;
+        resume_from = 0;
+    }
+    tracker.advance(resume_from);
+
+    if resume_from < file.size || file.size == 0 {
+        let mut last_reported = resume_from;
+        download::download_to_file(
+            client.content_request(&file.hash)?,
+            &partial_path,
+            resume_from,
+            file.size,
+            |current, _total| {
+                if tracker.is_cancelled() {
+                    return Err("Fetch cancelled".to_string());
+                }
+                tracker.advance(current.saturating_sub(last_reported));
+                last_reported = current;
+                Ok(())
+            },
+        )
+        .await?;
+    }
+
+    let digest = download::sha256_file(&partial_path).await?;
+    if !digest.eq_ignore_ascii_case(&file.hash) {
+        let _ = fs::remove_file(&partial_path);
+        return Err(format!(
+            "Hash mismatch for {:?}: expected {}, got {}",
+            file.dest, file.hash, digest
+        ));
+    }
+
+    fs::rename(&partial_path, &file.dest).map_err(|e| format!("Failed to move {:?} into place: {}", file.dest, e))
+}
+
+// Fetch an object by hash to `dest`, emitting `fetch_progress` events.
+// Files are written to `dest` directly, directories are recreated below it.
+// Interrupted fetches resume from the `.part` files left behind.
+#[tauri::command]
+pub async fn fetch_by_hash(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    hash: String,
+    dest: String,
+    transfer_id: String,
+) -> Result<String, String> {
+    client::validate_hash(&hash)?;
+    let client = client::client_for(&state)?;
+    let dest_path = PathBuf::from(&dest);
+    if let Some(parent) = dest_path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+
+    let cancelled = state.transfers.lock().unwrap().begin(&transfer_id)?;
+    add_log_entry(&state.logs, &format!("Fetching {} to {}", hash, dest));
+
+    let result = async {
+        let files = plan_fetch(&client, &hash, &dest_path).await?;
+        let total = files.iter().map(|f| f.size).sum();
+        let tracker = ProgressTracker::new(app_handle, "fetch_progress", &transfer_id, 0, total, cancelled);
+        for file in &files {
+            fetch_file(&client, file, &tracker).await?;
+        }
+        Ok::<_, String>(files.len())
+    }
+    .await;
+    state.transfers.lock().unwrap().finish(&transfer_id);
+
+    match result {
+        Ok(count) => {
+            add_log_entry(&state.logs, &format!("Fetched {} ({} files) to {}", hash, count, dest));
+            Ok(dest)
+        }
+        Err(e) => {
+            add_log_entry(&state.logs, &format!("Failed to fetch {}: {}", hash, e));
+            Err(e)
+        }
+    }
+}
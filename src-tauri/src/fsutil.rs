@@ -0,0 +1,54 @@
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use sha2::{Digest, Sha256};
+
+// A regular file found by `collect_files`
+#[derive(Debug, Clone)]
+pub struct FoundFile {
+    pub path: PathBuf,
+    // `/` separated path relative to the walked root
+    pub relative: String,
+    pub size: u64,
+}
+
+// Recursively collect the regular files below `dir`, with paths relative to `root`
+pub fn collect_files(root: &Path, dir: &Path, files: &mut Vec<FoundFile>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        let path = entry.path();
+        if metadata.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if metadata.is_file() {
+            let relative = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push(FoundFile {
+                path,
+                relative,
+                size: metadata.len(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Compute the hex encoded SHA-256 digest of a file; blocks while reading it
+pub fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
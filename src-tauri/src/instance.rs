@@ -0,0 +1,41 @@
+use tauri::{AppHandle, Manager};
+
+use crate::deeplink::is_deep_link;
+use crate::overrides::Overrides;
+use crate::tray::show_main_window;
+use crate::{add_log_entry, start_node_from_settings, AppState};
+
+// Handle another launch of the app, which exits right after forwarding its arguments here:
+// bring the window forward and act on the arguments that make sense for a running app
+pub fn on_second_instance(app_handle: &AppHandle, argv: Vec<String>, _cwd: String) {
+    show_main_window(app_handle);
+    let state = app_handle.state::<AppState>();
+
+    // Links are forwarded to the deep link handler by the single instance plugin
+    let options = argv.into_iter().skip(1).filter(|arg| !is_deep_link(arg));
+    let overrides = match Overrides::from_args(options) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            add_log_entry(&state.logs, &format!("Ignoring arguments of a second launch: {}", e));
+            return;
+        }
+    };
+
+    // Directories and ports are fixed when the app starts
+    if overrides.has_launch_options() {
+        add_log_entry(
+            &state.logs,
+            "Ignoring directory and port options of a second launch, restart the app to change them",
+        );
+    }
+
+    if overrides.autostart && !*state.is_running.lock().unwrap() {
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = start_node_from_settings(app_handle.clone()).await {
+                let state = app_handle.state::<AppState>();
+                add_log_entry(&state.logs, &format!("Failed to start node: {}", e));
+            }
+        });
+    }
+}
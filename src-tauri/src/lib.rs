@@ -1,58 +1,66 @@
-use std::process::{Child, Command, Stdio};
+use std::process::{Child, Stdio};
 use std::sync::{Arc, Mutex};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::fs;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 use std::thread;
 use serde::{Deserialize, Serialize};
-use tauri::{Emitter, Manager, State, WebviewWindowBuilder};
-use futures_util::StreamExt; // For stream processing
-use tokio::io::AsyncWriteExt; // For async file writing
-use dirs;
-
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct AppSettings {
-    db_path: Option<String>,
-}
-
-impl Default for AppSettings {
-    fn default() -> Self {
-        Self { db_path: None }
-    }
-}
-
-fn get_settings_path() -> PathBuf {
-    let mut config_path = dirs::config_dir().unwrap_or_else(|| {
-        // Fallback for systems where config_dir is not available
-        std::env::current_dir().unwrap_or_default()
-    });
-    config_path.push("OpenHash");
-    fs::create_dir_all(&config_path).expect("Failed to create config directory");
-    config_path.push("settings.json");
-    config_path
-}
-
-fn load_settings() -> AppSettings {
-    let settings_path = get_settings_path();
-    if settings_path.exists() {
-        let content = fs::read_to_string(settings_path).unwrap_or_default();
-        serde_json::from_str(&content).unwrap_or_default()
-    } else {
-        AppSettings::default()
-    }
-}
-
-fn save_settings(settings: &AppSettings) {
-    let settings_path = get_settings_path();
-    let content = serde_json::to_string_pretty(settings).unwrap();
-    fs::write(settings_path, content).expect("Failed to write settings");
-}
+use tauri::{Emitter, Manager, State};
+
+mod backup;
+mod cli;
+mod client;
+mod control;
+mod dashboard;
+mod deeplink;
+mod disk;
+mod download;
+mod fetch;
+mod fsutil;
+mod instance;
+mod login;
+mod migrate;
+mod monitor;
+mod node;
+mod notify;
+mod overrides;
+mod paths;
+mod peers;
+mod profile;
+mod reset;
+mod settings;
+mod supervisor;
+mod transfer;
+mod tray;
+mod updater;
+mod upload;
+mod webview;
+
+use deeplink::DeepLink;
+use monitor::ResourceMonitor;
+use notify::Category;
+pub use cli::run_cli;
+pub use deeplink::is_deep_link;
+pub use overrides::Overrides;
+use paths::StoragePaths;
+use reset::ResetTokens;
+use settings::{load_settings, modify_settings};
+use transfer::TransferRegistry;
+use updater::UpdateOutcome;
 
 #[tauri::command]
-fn set_custom_data_path(path: String) {
-    let mut settings = load_settings();
-    settings.db_path = Some(path);
-    save_settings(&settings);
+fn set_custom_data_path(app_handle: tauri::AppHandle, path: String) -> Result<(), String> {
+    if migrate::needs_migration(Path::new(&path))? {
+        return Err(format!(
+            "The current data directory holds the node data and {} doesn't, move the data there instead",
+            path
+        ));
+    }
+    modify_settings(&app_handle, |settings| {
+        settings.db_dir = Some(path);
+        Ok(())
+    })?;
+    Ok(())
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -65,23 +73,17 @@ pub struct NodeConfig {
     p2p_port: u16,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitHubRelease {
-    tag_name: String,
-    assets: Vec<GitHubAsset>,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-pub struct GitHubAsset {
-    name: String,
-    browser_download_url: String,
-}
-
 // Application state to manage the running process
 pub struct AppState {
     pub process: Arc<Mutex<Option<Child>>>,
     pub logs: Arc<Mutex<String>>,
     pub is_running: Arc<Mutex<bool>>,
+    pub monitor: Arc<Mutex<ResourceMonitor>>,
+    pub api_port: Arc<Mutex<Option<u16>>>,
+    pub transfers: Arc<Mutex<TransferRegistry>>,
+    pub resets: Arc<Mutex<ResetTokens>>,
+    pub restart_attempts: Arc<Mutex<u32>>,
+    pub deep_link: Arc<Mutex<Option<DeepLink>>>,
 }
 
 impl Default for AppState {
@@ -90,46 +92,31 @@ impl Default for AppState {
             process: Arc::new(Mutex::new(None)),
             logs: Arc::new(Mutex::new(String::new())),
             is_running: Arc::new(Mutex::new(false)),
+            monitor: Arc::new(Mutex::new(ResourceMonitor::default())),
+            api_port: Arc::new(Mutex::new(None)),
+            transfers: Arc::new(Mutex::new(TransferRegistry::default())),
+            resets: Arc::new(Mutex::new(ResetTokens::default())),
+            restart_attempts: Arc::new(Mutex::new(0)),
+            deep_link: Arc::new(Mutex::new(None)),
         }
     }
 }
 
-// Get the default data directory for the application
-fn get_default_data_dir() -> PathBuf {
-    let mut default_path = dirs::data_dir().unwrap_or_else(|| {
-        // Fallback for systems where data_dir is not available
-        std::env::current_dir().unwrap_or_default().join("data")
-    });
-    default_path.push("OpenHash");
-    default_path
-}
-
-// Get the base data directory for the application
-fn get_data_dir(db_path: Option<String>) -> PathBuf {
-    if let Some(path) = db_path.filter(|p| !p.is_empty()) {
-        return PathBuf::from(path);
-    }
-
-    let settings = load_settings();
-    if let Some(path) = settings.db_path.filter(|p| !p.is_empty()) {
-        PathBuf::from(path)
-    } else {
-        get_default_data_dir()
+// Get the parent directory of the node databases; a directory forced at launch
+// wins over the one passed by the UI, which wins over the settings
+fn get_db_dir(db_path: Option<String>) -> Result<PathBuf, String> {
+    if overrides::get().data_dir.is_none() {
+        if let Some(path) = db_path.filter(|p| !p.is_empty()) {
+            return Ok(PathBuf::from(path));
+        }
     }
+    Ok(StoragePaths::load()?.db_dir)
 }
 
 // Get the current data directory for the application
 #[tauri::command]
-fn get_current_data_path() -> String {
-    // First, check for a custom path in settings.
-    let settings = load_settings();
-    if let Some(path) = settings.db_path {
-        if !path.is_empty() {
-            return path;
-        }
-    }
-    // If no custom path is set, return the default path.
-    get_default_data_dir().to_string_lossy().into_owned()
+fn get_current_data_path() -> Result<String, String> {
+    Ok(StoragePaths::load()?.db_dir.to_string_lossy().into_owned())
 }
 
 // Add a log entry with timestamp
@@ -148,10 +135,8 @@ fn add_log_entry(logs: &Arc<Mutex<String>>, message: &str) {
 
 // Check if the openhash executable exists
 #[tauri::command]
-fn check_executable_exists(db_path: Option<String>) -> bool {
-    let data_dir = get_data_dir(db_path);
-    let executable_path = data_dir.join("openhash.exe");
-    executable_path.exists()
+fn check_executable_exists() -> Result<bool, String> {
+    Ok(StoragePaths::load()?.executable_path().exists())
 }
 
 // Get the current process status
@@ -163,18 +148,42 @@ async fn get_process_status(state: State<'_, AppState>) -> Result<bool, String>
 
 // Start the OpenHash node
 #[tauri::command]
-async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bool, String> {
-    let data_dir = get_data_dir(Some(config.db_path.clone()));
-    let executable_path = data_dir.join("openhash.exe");
+async fn start_node(
+    app_handle: tauri::AppHandle,
+    config: NodeConfig,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    launch_node(app_handle, config, state, true).await
+}
+
+// Start the node process. Automatic restarts keep the logs so the output that led to them stays visible.
+async fn launch_node(
+    app_handle: tauri::AppHandle,
+    config: NodeConfig,
+    state: State<'_, AppState>,
+    clear_logs: bool,
+) -> Result<bool, String> {
+    let storage_paths = StoragePaths::load()?;
+    let executable_path = storage_paths.executable_path();
     
     if !executable_path.exists() {
         return Err("OpenHash executable not found. Please download it first.".to_string());
     }
     
     // The --db argument for the daemon should point to a specific subdirectory
-    let final_db_path = data_dir.join("node1");
+    let final_db_path = get_db_dir(Some(config.db_path.clone()))?.join(paths::NODE_ID);
     fs::create_dir_all(&final_db_path).map_err(|e| format!("Failed to create DB directory: {}", e))?;
     
+    // Node output is also kept in a log file next to the other node logs
+    fs::create_dir_all(&storage_paths.logs_dir).map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    let log_file_path = node::log_file_path(&storage_paths);
+    let log_file = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_file_path)
+        .map(|file| Arc::new(Mutex::new(file)))
+        .map_err(|e| format!("Failed to open log file {:?}: {}", log_file_path, e))?;
+    
     // Check if a process is already running
     {
         let is_running = state.is_running.lock().unwrap();
@@ -182,27 +191,43 @@ async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bo
             return Err("Node is already running".to_string());
         }
     }
+    if let Some(pid) = node::running_pid(&storage_paths) {
+        return Err(format!("Node is already running outside the app (pid {})", pid));
+    }
+    
+    // Remember the ports so they survive app restarts, unless they were forced at launch
+    let overrides = overrides::get();
+    let settings = modify_settings(&app_handle, |settings| {
+        if overrides.api_port.is_none() {
+            settings.node.api_port = config.api_port;
+        }
+        if overrides.p2p_port.is_none() {
+            settings.node.p2p_port = config.p2p_port;
+        }
+        Ok(())
+    })?;
+    let config = NodeConfig {
+        api_port: overrides.api_port.unwrap_or(config.api_port),
+        p2p_port: overrides.p2p_port.unwrap_or(config.p2p_port),
+        ..config
+    };
+    
+    node::check_ports_free(config.api_port, config.p2p_port).inspect_err(|e| {
+        add_log_entry(&state.logs, e);
+        notify::notify(&app_handle, Category::PortConflict, "OpenHash node could not start", e);
+    })?;
     
     // Build the command
-    let mut cmd = Command::new(&executable_path);
-    cmd.arg("daemon")
-       .arg("--api-port")
-       .arg(config.api_port.to_string())
-       .arg("--db")
-       .arg(&final_db_path)
-       .arg("--p2p-port")
-       .arg(config.p2p_port.to_string())
-       .stdout(Stdio::piped())
+    let mut cmd = node::node_command(
+        &executable_path,
+        &final_db_path,
+        config.api_port,
+        config.p2p_port,
+        &settings.bootstrap_peers,
+    );
+    cmd.stdout(Stdio::piped())
        .stderr(Stdio::piped());
     
-    // Hide console window on Windows
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
-    }
-    
     // Start the process
     match cmd.spawn() {
         Ok(mut child) => {
@@ -213,7 +238,7 @@ async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bo
             }
             
             // Clear previous logs and add startup message
-            {
+            if clear_logs {
                 let mut logs_guard = state.logs.lock().unwrap();
                 logs_guard.clear();
             }
@@ -223,12 +248,14 @@ async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bo
             if let Some(stdout) = child.stdout.take() {
                 let logs_clone = Arc::clone(&state.logs);
                 let is_running_clone = Arc::clone(&state.is_running);
+                let log_file_clone = Arc::clone(&log_file);
                 thread::spawn(move || {
                     let reader = BufReader::new(stdout);
                     for line in reader.lines() {
                         match line {
                             Ok(line) => {
                                 add_log_entry(&logs_clone, &format!("STDOUT: {}", line));
+                                let _ = writeln!(log_file_clone.lock().unwrap(), "STDOUT: {}", line);
                             }
                             Err(_) => break,
                         }
@@ -246,12 +273,14 @@ async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bo
             if let Some(stderr) = child.stderr.take() {
                 let logs_clone = Arc::clone(&state.logs);
                 let is_running_clone = Arc::clone(&state.is_running);
+                let log_file_clone = Arc::clone(&log_file);
                 thread::spawn(move || {
                     let reader = BufReader::new(stderr);
                     for line in reader.lines() {
                         match line {
                             Ok(line) => {
                                 add_log_entry(&logs_clone, &format!("STDERR: {}", line));
+                                let _ = writeln!(log_file_clone.lock().unwrap(), "STDERR: {}", line);
                             }
                             Err(_) => break,
                         }
@@ -265,6 +294,20 @@ async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bo
                 });
             }
             
+            // Remember the API port so the client commands can reach the node
+            *state.api_port.lock().unwrap() = Some(config.api_port);
+            
+            // Begin sampling the resource usage of the new process
+            state.monitor.lock().unwrap().watch(child.id(), final_db_path.clone());
+            
+            // Detect the node exiting on its own
+            supervisor::watch_exit(app_handle.clone(), child.id());
+            
+            // Let the command line see the node started here
+            if let Err(e) = node::write_pid_file(&storage_paths, child.id()) {
+                add_log_entry(&state.logs, &e);
+            }
+            
             // Store the process
             let mut process_guard = state.process.lock().unwrap();
             *process_guard = Some(child);
@@ -279,6 +322,26 @@ async fn start_node(config: NodeConfig, state: State<'_, AppState>) -> Result<bo
     }
 }
 
+// Start the node with the stored configuration, as last used by `start_node`
+async fn start_node_from_settings(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    launch_node_from_settings(app_handle, true).await
+}
+
+// Restart the node after it exited on its own, keeping the logs of the previous run
+async fn restart_node_from_settings(app_handle: tauri::AppHandle) -> Result<bool, String> {
+    launch_node_from_settings(app_handle, false).await
+}
+
+async fn launch_node_from_settings(app_handle: tauri::AppHandle, clear_logs: bool) -> Result<bool, String> {
+    let settings = load_settings()?;
+    let config = NodeConfig {
+        db_path: StoragePaths::resolve(&settings).db_dir.to_string_lossy().into_owned(),
+        api_port: settings.node.api_port,
+        p2p_port: settings.node.p2p_port,
+    };
+    launch_node(app_handle.clone(), config, app_handle.state(), clear_logs).await
+}
+
 // Stop the OpenHash node
 #[tauri::command]
 async fn stop_node(state: State<'_, AppState>) -> Result<bool, String> {
@@ -287,10 +350,15 @@ async fn stop_node(state: State<'_, AppState>) -> Result<bool, String> {
         let mut is_running = state.is_running.lock().unwrap();
         *is_running = false;
     }
+    state.monitor.lock().unwrap().unwatch();
+    *state.api_port.lock().unwrap() = None;
     
     let mut process_guard = state.process.lock().unwrap();
     
     if let Some(mut child) = process_guard.take() {
+        if let Ok(storage_paths) = StoragePaths::load() {
+            node::remove_pid_file(&storage_paths);
+        }
         match child.kill() {
             Ok(_) => {
                 // Wait for the process to terminate
@@ -315,194 +383,32 @@ async fn stop_node(state: State<'_, AppState>) -> Result<bool, String> {
 async fn check_and_download_update(
     app_handle: tauri::AppHandle,
     state: State<'_, AppState>,
-    db_path: Option<String>,
 ) -> Result<bool, String> {
-    const GITHUB_API_URL: &str = "https://api.github.com/repos/nnlgsakib/open-hash-db/releases/latest";
-    
-    add_log_entry(&state.logs, "Checking for updates...");
-    
-    // Fetch the latest release information
-    let client = reqwest::Client::new();
-    let response = client
-        .get(GITHUB_API_URL)
-        .header("User-Agent", "OpenHash-Wrapper")
-        .send()
-        .await
-        .map_err(|e| {
-            let error_msg = format!("Failed to fetch release info: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-    
-    if !response.status().is_success() {
-        let error_msg = "Failed to fetch release information from GitHub".to_string();
-        add_log_entry(&state.logs, &error_msg);
-        return Err(error_msg);
-    }
-    
-    let release: GitHubRelease = response
-        .json()
-        .await
-        .map_err(|e| {
-            let error_msg = format!("Failed to parse release info: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-    
-    add_log_entry(&state.logs, &format!("Found release: {}", release.tag_name));
-    
-    // Find the openhash.exe asset
-    let asset = release
-        .assets
-        .iter()
-        .find(|asset| asset.name == "openhash.exe")
-        .ok_or_else(|| {
-            let error_msg = "openhash.exe not found in release assets".to_string();
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
+    let log = |message: &str| add_log_entry(&state.logs, message);
+    let storage_paths = StoragePaths::load().inspect_err(|e| log(e))?;
     
-    // Determine the executable path
-    let data_dir = get_data_dir(db_path);
-    fs::create_dir_all(&data_dir).map_err(|e| format!("Failed to create data directory: {}", e))?;
-    let executable_path = data_dir.join("openhash.exe");
-    let mut downloaded_bytes: u64 = 0;
-
-    // Get total size from HEAD request first
-    let head_response = client
-        .head(&asset.browser_download_url)
-        .send()
-        .await
-        .map_err(|e| {
-            let error_msg = format!("Failed to get file size: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-    let mut total_size = head_response.content_length().unwrap_or(0);
-
-    // Check if a partial file exists and get its size for resuming
-    if executable_path.exists() {
-        match fs::metadata(&executable_path) {
-            Ok(metadata) => {
-                downloaded_bytes = metadata.len();
-                if total_size > 0 && downloaded_bytes == total_size {
-                    add_log_entry(&state.logs, "openhash.exe is already up to date.");
-                    app_handle.emit("download_complete", ()).map_err(|e| {
-                        let error_msg = format!("Failed to emit download_complete event: {}", e);
-                        add_log_entry(&state.logs, &error_msg);
-                        error_msg
-                    })?;
-                    return Ok(true);
-                } else if total_size > 0 && downloaded_bytes < total_size {
-                    add_log_entry(&state.logs, &format!("Resuming download from {} bytes.", downloaded_bytes));
-                } else if downloaded_bytes > 0 {
-                    add_log_entry(&state.logs, "Could not verify file size. Restarting download.");
-                    fs::remove_file(&executable_path).map_err(|e| format!("Failed to remove existing file: {}", e))?;
-                    downloaded_bytes = 0;
-                }
-            },
-            Err(e) => {
-                let error_msg = format!("Failed to get metadata for existing file: {}", e);
-                add_log_entry(&state.logs, &error_msg);
-                return Err(error_msg);
-            }
-        }
-    }
-
-    add_log_entry(&state.logs, &format!("Downloading openhash.exe to {:?}...", executable_path));
-    
-    // Download the executable with progress and resumability
-    let mut request_builder = client.get(&asset.browser_download_url);
-    if downloaded_bytes > 0 {
-        request_builder = request_builder.header(reqwest::header::RANGE, format!("bytes={}-", downloaded_bytes));
-    }
-
-    let download_response = request_builder
-        .send()
-        .await
-        .map_err(|e| {
-            let error_msg = format!("Failed to download executable: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-    
-    if !download_response.status().is_success() && download_response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
-        let error_msg = format!("Failed to download executable: Status {}", download_response.status());
-        add_log_entry(&state.logs, &error_msg);
-        return Err(error_msg);
-    }
-
-    // Update total_size from the actual download response if not already set
-    if total_size == 0 {
-        total_size = download_response.content_length().unwrap_or(0) + downloaded_bytes;
-    }
-
-    let mut file = tokio::fs::OpenOptions::new()
-        .create(true)
-        .append(downloaded_bytes > 0) // Only append if resuming
-        .write(true)
-        .open(&executable_path)
-        .await
-        .map_err(|e| {
-            let error_msg = format!("Failed to open file for writing: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-
-    let mut stream = download_response.bytes_stream();
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| {
-            let error_msg = format!("Error while downloading chunk: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-        file.write_all(&chunk)
-            .await
-            .map_err(|e| {
-                let error_msg = format!("Error while writing to file: {}", e);
-                add_log_entry(&state.logs, &error_msg);
-                error_msg
-            })?;
-        downloaded_bytes += chunk.len() as u64;
-
-        // Emit progress event
-        add_log_entry(&state.logs, &format!("Emitting download_progress: {}/{}", downloaded_bytes, total_size));
-        app_handle.emit("download_progress", DownloadProgress {
-            current: downloaded_bytes,
-            total: total_size,
-        }).map_err(|e| {
-            let error_msg = format!("Failed to emit download_progress event: {}", e);
-            add_log_entry(&state.logs, &error_msg);
-            error_msg
-        })?;
-        add_log_entry(&state.logs, "Emitted download_progress");
-    }
+    let outcome = updater::update_executable(
+        &storage_paths,
+        &log,
+        |tag| notify::notify(&app_handle, Category::Update, "OpenHash update available", &format!("Downloading {}", tag)),
+        |current, total| {
+            // Emit progress event
+            add_log_entry(&state.logs, &format!("Emitting download_progress: {}/{}", current, total));
+            app_handle.emit("download_progress", DownloadProgress { current, total })
+                .map_err(|e| format!("Failed to emit download_progress event: {}", e))?;
+            add_log_entry(&state.logs, "Emitted download_progress");
+            Ok(())
+        },
+    )
+    .await
+    .inspect_err(|e| log(e))?;
     
-    // Make it executable on Unix systems
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = fs::metadata(&executable_path)
-            .map_err(|e| {
-                let error_msg = format!("Failed to get file metadata: {}", e);
-                add_log_entry(&state.logs, &error_msg);
-                error_msg
-            })?
-            .permissions();
-        perms.set_mode(0o755);
-        fs::set_permissions(&executable_path, perms)
-            .map_err(|e| {
-                let error_msg = format!("Failed to set executable permissions: {}", e);
-                add_log_entry(&state.logs, &error_msg);
-                error_msg
-            })?;
+    if let UpdateOutcome::Installed(tag) = &outcome {
+        notify::notify(&app_handle, Category::Update, "OpenHash updated", &format!("{} has been installed", tag));
     }
-    
-    add_log_entry(&state.logs, "Download completed successfully");
     app_handle.emit("download_complete", ()).map_err(|e| {
         let error_msg = format!("Failed to emit download_complete event: {}", e);
-        add_log_entry(&state.logs, &error_msg);
+        log(&error_msg);
         error_msg
     })?;
     Ok(true)
@@ -529,32 +435,6 @@ async fn clear_logs(state: State<'_, AppState>) -> Result<(), String> {
     Ok(())
 }
 
-#[tauri::command]
-async fn open_webview(app_handle: tauri::AppHandle, port: u16) -> Result<(), String> {
-    let url = format!("http://localhost:{}", port);
-
-    // Check if a window with this label already exists
-    if app_handle.get_webview_window(&format!("webview_{}", port)).is_some() {
-        return Err(format!("Webview for port {} is already open.", port));
-    }
-
-    // Create a new window
-    let webview_window = WebviewWindowBuilder::new(
-        &app_handle,
-        format!("webview_{}", port), // Unique label
-        tauri::WebviewUrl::External(url.parse().unwrap()),
-    )
-    .title(format!("Webview - Port {}", port))
-    .inner_size(800.0, 600.0)
-    .build()
-    .map_err(|e| format!("Failed to create webview window: {}", e))?;
-
-    webview_window.set_focus().map_err(|e| format!("Failed to focus webview window: {}", e))?;
-
-    Ok(())
-}
-
-
 // Legacy greet command (keeping for compatibility)
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -563,10 +443,28 @@ fn greet(name: &str) -> String {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let overrides = Overrides::from_env().unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        Overrides::default()
+    });
+    run_with_overrides(overrides)
+}
+
+// Run the app with settings forced from the environment or the command line
+pub fn run_with_overrides(overrides: Overrides) {
+    overrides::init(overrides);
+
     tauri::Builder::default()
+        // Must come first: a second launch hands its arguments to the running app and exits
+        .plugin(tauri_plugin_single_instance::init(|app, argv, cwd| {
+            instance::on_second_instance(app, argv, cwd);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .manage(AppState::default())
+        .register_asynchronous_uri_scheme_protocol(dashboard::SCHEME, dashboard::protocol)
         .invoke_handler(tauri::generate_handler![
             greet,
             check_executable_exists,
@@ -577,9 +475,76 @@ pub fn run() {
             get_logs,
             clear_logs,
             get_current_data_path,
-            set_custom_data_path
+            set_custom_data_path,
+            monitor::get_resource_history,
+            client::add_content,
+            client::get_content,
+            client::list_objects,
+            client::pin_object,
+            client::unpin_object,
+            client::list_peers,
+            upload::add_path,
+            fetch::fetch_by_hash,
+            peers::connect_peer,
+            peers::disconnect_peer,
+            peers::get_bootstrap_peers,
+            peers::add_bootstrap_peer,
+            peers::remove_bootstrap_peer,
+            settings::get_settings,
+            settings::update_settings,
+            settings::take_settings_error,
+            profile::export_profile,
+            profile::import_profile,
+            transfer::cancel_transfer,
+            overrides::get_overrides,
+            migrate::migrate_data_dir,
+            migrate::data_dir_needs_migration,
+            paths::get_storage_paths,
+            backup::backup_node,
+            backup::restore_node,
+            disk::get_disk_usage,
+            reset::prepare_reset,
+            reset::reset_node,
+            login::enable_launch_at_login,
+            login::disable_launch_at_login,
+            login::get_launch_at_login,
+            webview::open_webview,
+            deeplink::take_deep_link
         ])
         .setup(|_app| {
+            monitor::spawn_sampler(_app.handle().clone());
+            backup::spawn_scheduler(_app.handle().clone());
+            disk::spawn_watcher(_app.handle().clone());
+            control::spawn_server(_app.handle().clone());
+
+            // Without a tray the app keeps working, closing the window just quits it
+            if let Err(e) = tray::setup(_app.handle()) {
+                let state = _app.state::<AppState>();
+                add_log_entry(&state.logs, &format!("Failed to create tray icon: {}", e));
+            }
+
+            // Start the node with the last used configuration when asked to at launch or in the settings
+            let auto_start = match load_settings() {
+                Ok(settings) => settings.node.auto_start,
+                Err(e) => {
+                    let state = _app.state::<AppState>();
+                    add_log_entry(&state.logs, &format!("Failed to load settings: {}", e));
+                    false
+                }
+            };
+            if overrides::get().autostart || auto_start {
+                let app_handle = _app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = start_node_from_settings(app_handle.clone()).await {
+                        let state = app_handle.state::<AppState>();
+                        add_log_entry(&state.logs, &format!("Failed to auto-start node: {}", e));
+                    }
+                });
+            }
+
+            // Handle `openhash://` links, including the one the app was launched with
+            deeplink::setup(_app.handle());
+
             #[cfg(debug_assertions)] // only enable for debug builds
             {
                 use tauri::Manager;
@@ -590,6 +555,10 @@ pub fn run() {
             }
             Ok(())
         })
+        .on_window_event(|window, event| {
+            webview::on_window_event(window, event);
+            tray::on_window_event(window, event);
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
@@ -0,0 +1,208 @@
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+use std::path::PathBuf;
+
+// Name the login entry is registered under
+#[cfg(any(target_os = "linux", windows))]
+const ENTRY_NAME: &str = "openhash-desktop";
+
+// Path to start the app from. AppImages run from a temporary mount, so the image itself is used.
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+fn app_executable() -> Result<PathBuf, String> {
+    if let Some(appimage) = std::env::var_os("APPIMAGE").filter(|p| !p.is_empty()) {
+        return Ok(PathBuf::from(appimage));
+    }
+    std::env::current_exe().map_err(|e| format!("Failed to locate the app executable: {}", e))
+}
+
+// XDG autostart entry
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::{app_executable, ENTRY_NAME};
+
+    fn entry_path() -> Result<PathBuf, String> {
+        let config_dir = dirs::config_dir().ok_or_else(|| "No config directory for autostart entries".to_string())?;
+        Ok(config_dir.join("autostart").join(format!("{}.desktop", ENTRY_NAME)))
+    }
+
+    // Quote a path for the Exec key of a desktop entry
+    fn quote_exec(path: &str) -> String {
+        let escaped = path
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('`', "\\`")
+            .replace('$', "\\$");
+        format!("\"{}\"", escaped.replace('%', "%%"))
+    }
+
+    pub fn enable() -> Result<(), String> {
+        let path = entry_path()?;
+        let exec = quote_exec(&app_executable()?.to_string_lossy());
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=OpenHash\nComment=Start OpenHash on login\nExec={}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            exec
+        );
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        fs::write(&path, entry).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    pub fn disable() -> Result<(), String> {
+        let path = entry_path()?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {:?}: {}", path, e)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_enabled() -> Result<bool, String> {
+        Ok(entry_path()?.exists())
+    }
+}
+
+// Launch agent in the user's library
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::app_executable;
+
+    const AGENT_LABEL: &str = "com.openhash-desktop";
+
+    fn agent_path() -> Result<PathBuf, String> {
+        let home = dirs::home_dir().ok_or_else(|| "No home directory for launch agents".to_string())?;
+        Ok(home.join("Library/LaunchAgents").join(format!("{}.plist", AGENT_LABEL)))
+    }
+
+    fn escape_xml(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+
+    pub fn enable() -> Result<(), String> {
+        let path = agent_path()?;
+        let plist = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+            AGENT_LABEL,
+            escape_xml(&app_executable()?.to_string_lossy())
+        );
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create {:?}: {}", parent, e))?;
+        }
+        fs::write(&path, plist).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+    }
+
+    pub fn disable() -> Result<(), String> {
+        let path = agent_path()?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to remove {:?}: {}", path, e)),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn is_enabled() -> Result<bool, String> {
+        Ok(agent_path()?.exists())
+    }
+}
+
+// Value under the current user's Run key, managed through reg.exe
+#[cfg(windows)]
+mod platform {
+    use std::os::windows::process::CommandExt;
+    use std::process::{Command, Output};
+
+    use super::{app_executable, ENTRY_NAME};
+
+    const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+    fn reg(args: &[&str]) -> Result<Output, String> {
+        Command::new("reg")
+            .args(args)
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("Failed to run reg.exe: {}", e))
+    }
+
+    pub fn enable() -> Result<(), String> {
+        let command = format!("\"{}\"", app_executable()?.to_string_lossy());
+        let output = reg(&["add", RUN_KEY, "/v", ENTRY_NAME, "/t", "REG_SZ", "/d", &command, "/f"])?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!("Failed to register login item: {}", String::from_utf8_lossy(&output.stderr).trim()))
+        }
+    }
+
+    pub fn disable() -> Result<(), String> {
+        if !is_enabled()? {
+            return Ok(());
+        }
+        let output = reg(&["delete", RUN_KEY, "/v", ENTRY_NAME, "/f"])?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            Err(format!("Failed to remove login item: {}", String::from_utf8_lossy(&output.stderr).trim()))
+        }
+    }
+
+    pub fn is_enabled() -> Result<bool, String> {
+        Ok(reg(&["query", RUN_KEY, "/v", ENTRY_NAME])?.status.success())
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+mod platform {
+    pub fn enable() -> Result<(), String> {
+        Err("Launching at login is not supported on this platform".to_string())
+    }
+
+    pub fn disable() -> Result<(), String> {
+        Ok(())
+    }
+
+    pub fn is_enabled() -> Result<bool, String> {
+        Ok(false)
+    }
+}
+
+// Start the app when the user logs in
+#[tauri::command]
+pub fn enable_launch_at_login() -> Result<bool, String> {
+    platform::enable()?;
+    platform::is_enabled()
+}
+
+// Stop starting the app when the user logs in
+#[tauri::command]
+pub fn disable_launch_at_login() -> Result<bool, String> {
+    platform::disable()?;
+    platform::is_enabled()
+}
+
+// Check whether the app is started when the user logs in
+#[tauri::command]
+pub fn get_launch_at_login() -> Result<bool, String> {
+    platform::is_enabled()
+}
@@ -1,6 +1,25 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+use tauri_openhash_wrapper_lib::Overrides;
+
 fn main() {
-    tauri_openhash_wrapper_lib::run()
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    // Subcommands like `start` or `status` manage the node without opening a window
+    if let Some(code) = tauri_openhash_wrapper_lib::run_cli(&args) {
+        std::process::exit(code);
+    }
+
+    // Settings forced through OPENHASH_* variables and arguments like --data-dir or --start
+    // `openhash://` links the app is opened with are handled once it runs
+    let options = args.into_iter().filter(|arg| !tauri_openhash_wrapper_lib::is_deep_link(arg));
+    let overrides = match Overrides::from_env_and_args(options) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(2);
+        }
+    };
+    tauri_openhash_wrapper_lib::run_with_overrides(overrides)
 }
@@ -0,0 +1,213 @@
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+
+use crate::backup::backups_dir;
+use crate::download;
+use crate::fsutil::{collect_files, FoundFile};
+use crate::node;
+use crate::overrides;
+use crate::settings::{load_settings, modify_settings};
+use crate::transfer::ProgressTracker;
+use crate::paths::{StoragePaths, KNOWN_NODES};
+use crate::{add_log_entry, start_node_from_settings, stop_node, AppState};
+
+// Transfer id used for progress events and `cancel_transfer`
+const MIGRATION_TRANSFER_ID: &str = "data_migration";
+
+// Copy one file in chunks, reporting progress and honouring cancellation
+fn copy_file(from: &Path, to: &Path, tracker: &ProgressTracker) -> Result<(), String> {
+    if let Some(parent) = to.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("Failed to create directory {:?}: {}", parent, e))?;
+    }
+    let mut source = File::open(from).map_err(|e| format!("Failed to open {:?}: {}", from, e))?;
+    let mut target = File::create(to).map_err(|e| format!("Failed to create {:?}: {}", to, e))?;
+    let mut buffer = vec![0u8; 1024 * 1024];
+    loop {
+        if tracker.is_cancelled() {
+            return Err("Migration cancelled".to_string());
+        }
+        let read = source.read(&mut buffer).map_err(|e| format!("Failed to read {:?}: {}", from, e))?;
+        if read == 0 {
+            break;
+        }
+        target
+            .write_all(&buffer[..read])
+            .map_err(|e| format!("Failed to write {:?}: {}", to, e))?;
+        tracker.advance(read as u64);
+    }
+    target.sync_all().map_err(|e| format!("Failed to write {:?}: {}", to, e))
+}
+
+// Check that the new location is usable and doesn't overlap the old one
+fn check_target(old_dir: &Path, new_dir: &Path) -> Result<(), String> {
+    if !new_dir.is_absolute() {
+        return Err("The new data directory must be an absolute path".to_string());
+    }
+    let old_canonical = fs::canonicalize(old_dir).unwrap_or_else(|_| old_dir.to_path_buf());
+    let new_canonical = new_dir
+        .parent()
+        .and_then(|parent| fs::canonicalize(parent).ok())
+        .and_then(|parent| new_dir.file_name().map(|name| parent.join(name)))
+        .unwrap_or_else(|| new_dir.to_path_buf());
+    if new_canonical.starts_with(&old_canonical) || old_canonical.starts_with(&new_canonical) {
+        return Err("The new data directory must not contain or be inside the current one".to_string());
+    }
+    if new_dir.exists() {
+        let mut entries = fs::read_dir(new_dir).map_err(|e| format!("Failed to read {:?}: {}", new_dir, e))?;
+        if entries.next().is_some() {
+            return Err(format!("The new data directory {:?} is not empty", new_dir));
+        }
+    }
+    Ok(())
+}
+
+// Whether a directory holds a database of any managed node
+fn has_node_data(dir: &Path) -> bool {
+    KNOWN_NODES.iter().any(|node_id| {
+        fs::read_dir(dir.join(node_id)).is_ok_and(|mut entries| entries.next().is_some())
+    })
+}
+
+// Whether switching the database directory to `new_dir` would leave the node data behind,
+// so the node would start over with an empty database
+pub fn needs_migration(new_dir: &Path) -> Result<bool, String> {
+    let old_dir = StoragePaths::load()?.db_dir;
+    Ok(canonical(&old_dir) != canonical(new_dir) && has_node_data(&old_dir) && !has_node_data(new_dir))
+}
+
+// Tell the UI whether a new database directory has to be reached with `migrate_data_dir`
+#[tauri::command]
+pub fn data_dir_needs_migration(path: String) -> Result<bool, String> {
+    needs_migration(Path::new(&path))
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Remove the migrated node directories, then the old directory itself once it is empty.
+// It is kept when the binaries, logs or backups directory is inside it.
+fn remove_old_data(old_dir: &Path, other_dirs: &[PathBuf]) -> Result<String, String> {
+    for node_id in KNOWN_NODES {
+        let node_dir = old_dir.join(node_id);
+        if node_dir.exists() {
+            fs::remove_dir_all(&node_dir).map_err(|e| format!("Failed to remove {:?}: {}", node_dir, e))?;
+        }
+    }
+    let old_canonical = canonical(old_dir);
+    if other_dirs.iter().any(|dir| canonical(dir).starts_with(&old_canonical)) {
+        return Ok(format!("Removed the old node data, kept {:?} as it holds other app files", old_dir));
+    }
+    match fs::remove_dir(old_dir) {
+        Ok(_) => Ok(format!("Removed old data directory {:?}", old_dir)),
+        Err(_) => Ok(format!("Removed the old node data, kept {:?} as it is not empty", old_dir)),
+    }
+}
+
+// Copy the data directory, verifying every file against its source
+async fn copy_data_dir(old_dir: &Path, new_dir: &Path, tracker: ProgressTracker, files: Vec<FoundFile>) -> Result<(), String> {
+    let (from_root, to_root) = (old_dir.to_path_buf(), new_dir.to_path_buf());
+    let copied = files.clone();
+    tokio::task::spawn_blocking(move || {
+        fs::create_dir_all(&to_root).map_err(|e| format!("Failed to create {:?}: {}", to_root, e))?;
+        for file in &copied {
+            copy_file(&from_root.join(&file.relative), &to_root.join(&file.relative), &tracker)?;
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| format!("Copy task failed: {}", e))??;
+
+    for file in &files {
+        let (source, target) = (old_dir.join(&file.relative), new_dir.join(&file.relative));
+        let target_size = fs::metadata(&target).map(|m| m.len()).unwrap_or(u64::MAX);
+        if target_size != file.size || download::sha256_file(&source).await? != download::sha256_file(&target).await? {
+            return Err(format!("Verification failed for {:?}", file.relative));
+        }
+    }
+    Ok(())
+}
+
+// Move the node databases to a new location, emitting
+// `migration_progress` events. The node is stopped during the copy and restarted afterwards.
+#[tauri::command]
+pub async fn migrate_data_dir(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    new_path: String,
+    remove_old: bool,
+) -> Result<String, String> {
+    if overrides::get().data_dir.is_some() {
+        return Err("The database directory is set at launch and can't be migrated".to_string());
+    }
+    let settings = load_settings()?;
+    let storage_paths = StoragePaths::resolve(&settings);
+    let old_dir = storage_paths.db_dir.clone();
+    let new_dir = PathBuf::from(&new_path);
+    check_target(&old_dir, &new_dir)?;
+
+    // Only the node databases move; the directory may also hold the binary or other files
+    let mut files = Vec::new();
+    for node_id in KNOWN_NODES {
+        let node_dir = old_dir.join(node_id);
+        if node_dir.exists() {
+            collect_files(&old_dir, &node_dir, &mut files).map_err(|e| format!("Failed to read {:?}: {}", node_dir, e))?;
+        }
+    }
+    let total = files.iter().map(|file| file.size).sum();
+
+    let was_running = *state.is_running.lock().unwrap();
+    node::check_not_running_outside(&storage_paths, was_running)?;
+    if was_running {
+        stop_node(state.clone()).await?;
+    }
+
+    let cancelled = state.transfers.lock().unwrap().begin(MIGRATION_TRANSFER_ID)?;
+    let tracker = ProgressTracker::new(app_handle.clone(), "migration_progress", MIGRATION_TRANSFER_ID, 0, total, cancelled);
+    add_log_entry(&state.logs, &format!("Migrating data from {:?} to {:?} ({} bytes)", old_dir, new_dir, total));
+
+    let new_dir_existed = new_dir.exists();
+    let result = copy_data_dir(&old_dir, &new_dir, tracker, files).await;
+    state.transfers.lock().unwrap().finish(MIGRATION_TRANSFER_ID);
+
+    let result = result.and_then(|_| {
+        modify_settings(&app_handle, |settings| {
+            settings.db_dir = Some(new_path.clone());
+            Ok(())
+        })
+    });
+
+    match result {
+        Ok(_) => {
+            add_log_entry(&state.logs, "Data migration completed and verified");
+            if remove_old {
+                let other_dirs = [storage_paths.bin_dir.clone(), storage_paths.logs_dir.clone(), backups_dir(&settings)];
+                match remove_old_data(&old_dir, &other_dirs) {
+                    Ok(message) => add_log_entry(&state.logs, &message),
+                    Err(e) => add_log_entry(&state.logs, &format!("Failed to remove old data: {}", e)),
+                }
+            }
+        }
+        Err(e) => {
+            // The old data is untouched; drop the incomplete copy, keeping a previously empty target
+            let _ = fs::remove_dir_all(&new_dir);
+            if new_dir_existed {
+                let _ = fs::create_dir(&new_dir);
+            }
+            add_log_entry(&state.logs, &format!("Data migration failed: {}", e));
+            if was_running {
+                if let Err(restart_error) = start_node_from_settings(app_handle.clone()).await {
+                    add_log_entry(&state.logs, &format!("Failed to restart node: {}", restart_error));
+                }
+            }
+            return Err(e);
+        }
+    }
+
+    if was_running {
+        start_node_from_settings(app_handle.clone()).await?;
+    }
+    Ok(new_path)
+}
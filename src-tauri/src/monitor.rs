@@ -0,0 +1,267 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+use crate::AppState;
+
+// How often the running node is sampled
+const SAMPLE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Number of samples kept in memory (one hour at the default interval)
+const HISTORY_CAPACITY: usize = 720;
+
+// How long a measured database size is reused; walking a large store on every sample is costly
+const DB_SIZE_MAX_AGE: Duration = Duration::from_secs(60);
+
+// Last measured size of each database directory and when it was taken
+static DB_SIZES: Mutex<Vec<(PathBuf, Instant, u64)>> = Mutex::new(Vec::new());
+
+// A single resource usage sample of the node process
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSample {
+    timestamp: String,
+    pid: u32,
+    cpu_percent: f32,
+    memory_bytes: u64,
+    threads: Option<u32>,
+    open_files: Option<u32>,
+    disk_read_bytes: Option<u64>,
+    disk_write_bytes: Option<u64>,
+    db_size_bytes: u64,
+}
+
+// Resource history for the currently monitored node process
+pub struct ResourceMonitor {
+    history: VecDeque<ResourceSample>,
+    target: Option<(u32, PathBuf)>,
+}
+
+impl Default for ResourceMonitor {
+    fn default() -> Self {
+        Self {
+            history: VecDeque::with_capacity(HISTORY_CAPACITY),
+            target: None,
+        }
+    }
+}
+
+impl ResourceMonitor {
+    // Start monitoring a freshly spawned node, discarding the previous history
+    pub fn watch(&mut self, pid: u32, db_path: PathBuf) {
+        self.history.clear();
+        self.target = Some((pid, db_path));
+    }
+
+    // Stop monitoring; the collected history is kept for inspection
+    pub fn unwatch(&mut self) {
+        self.target = None;
+    }
+
+    // Most recent sample, if any
+    pub fn latest(&self) -> Option<ResourceSample> {
+        self.history.back().cloned()
+    }
+
+    fn push(&mut self, sample: ResourceSample) {
+        if self.history.len() == HISTORY_CAPACITY {
+            self.history.pop_front();
+        }
+        self.history.push_back(sample);
+    }
+}
+
+// Raw per-process statistics gathered from the platform
+struct ProcessStats {
+    cpu_percent: f32,
+    memory_bytes: u64,
+    threads: Option<u32>,
+    open_files: Option<u32>,
+    disk_read_bytes: Option<u64>,
+    disk_write_bytes: Option<u64>,
+}
+
+// Linux sampler reading directly from /proc
+#[cfg(target_os = "linux")]
+struct ProcessSampler {
+    clock_ticks: f64,
+    last_cpu: Option<(u32, u64, std::time::Instant)>,
+}
+
+#[cfg(target_os = "linux")]
+impl ProcessSampler {
+    fn new() -> Self {
+        let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+        Self {
+            clock_ticks: if ticks > 0 { ticks as f64 } else { 100.0 },
+            last_cpu: None,
+        }
+    }
+
+    fn sample(&mut self, pid: u32) -> Option<ProcessStats> {
+        let proc_dir = PathBuf::from(format!("/proc/{}", pid));
+
+        // Fields after the command name, which may itself contain spaces
+        let stat = fs::read_to_string(proc_dir.join("stat")).ok()?;
+        let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+        let utime: u64 = fields.get(11)?.parse().ok()?;
+        let stime: u64 = fields.get(12)?.parse().ok()?;
+        let threads = fields.get(17).and_then(|t| t.parse().ok());
+
+        let cpu_ticks = utime + stime;
+        let now = std::time::Instant::now();
+        let cpu_percent = match self.last_cpu {
+            Some((last_pid, last_ticks, last_time)) if last_pid == pid => {
+                let elapsed = now.duration_since(last_time).as_secs_f64();
+                let used = cpu_ticks.saturating_sub(last_ticks) as f64 / self.clock_ticks;
+                if elapsed > 0.0 { (used / elapsed * 100.0) as f32 } else { 0.0 }
+            }
+            _ => 0.0,
+        };
+        self.last_cpu = Some((pid, cpu_ticks, now));
+
+        let status = fs::read_to_string(proc_dir.join("status")).unwrap_or_default();
+        let memory_bytes = status
+            .lines()
+            .find_map(|line| line.strip_prefix("VmRSS:"))
+            .and_then(|value| value.trim().trim_end_matches("kB").trim().parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+            .unwrap_or(0);
+
+        let open_files = fs::read_dir(proc_dir.join("fd"))
+            .ok()
+            .map(|entries| entries.count() as u32);
+
+        let io = fs::read_to_string(proc_dir.join("io")).unwrap_or_default();
+        let io_field = |name: &str| {
+            io.lines()
+                .find_map(|line| line.strip_prefix(name))
+                .and_then(|value| value.trim().parse::<u64>().ok())
+        };
+
+        Some(ProcessStats {
+            cpu_percent,
+            memory_bytes,
+            threads,
+            open_files,
+            disk_read_bytes: io_field("read_bytes:"),
+            disk_write_bytes: io_field("write_bytes:"),
+        })
+    }
+}
+
+// Sampler for other platforms backed by sysinfo
+#[cfg(not(target_os = "linux"))]
+struct ProcessSampler {
+    system: sysinfo::System,
+}
+
+#[cfg(not(target_os = "linux"))]
+impl ProcessSampler {
+    fn new() -> Self {
+        Self { system: sysinfo::System::new() }
+    }
+
+    fn sample(&mut self, pid: u32) -> Option<ProcessStats> {
+        let pid = sysinfo::Pid::from_u32(pid);
+        if !self.system.refresh_process(pid) {
+            return None;
+        }
+        let process = self.system.process(pid)?;
+        let disk_usage = process.disk_usage();
+
+        Some(ProcessStats {
+            cpu_percent: process.cpu_usage(),
+            memory_bytes: process.memory(),
+            threads: process.tasks().map(|tasks| tasks.len() as u32),
+            open_files: None,
+            disk_read_bytes: Some(disk_usage.total_read_bytes),
+            disk_write_bytes: Some(disk_usage.total_written_bytes),
+        })
+    }
+}
+
+// Recursively compute the size of a directory in bytes
+fn dir_size(path: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+// Size of a database directory, measured again only once the last measurement is a minute old.
+// Shared by the resource samples and the disk checks so the store is walked once per minute.
+pub fn cached_dir_size(path: &Path) -> u64 {
+    if let Some((_, measured, size)) = DB_SIZES.lock().unwrap().iter().find(|(p, _, _)| p == path) {
+        if measured.elapsed() < DB_SIZE_MAX_AGE {
+            return *size;
+        }
+    }
+    // Measure without holding the lock
+    let size = dir_size(path);
+    let mut sizes = DB_SIZES.lock().unwrap();
+    sizes.retain(|(p, _, _)| p != path);
+    sizes.push((path.to_path_buf(), Instant::now(), size));
+    size
+}
+
+// Sample the monitored node periodically and emit `resource_sample` events
+pub fn spawn_sampler(app_handle: AppHandle) {
+    let monitor = Arc::clone(&app_handle.state::<AppState>().monitor);
+    thread::spawn(move || {
+        let mut sampler = ProcessSampler::new();
+        loop {
+            thread::sleep(SAMPLE_INTERVAL);
+
+            let target = monitor.lock().unwrap().target.clone();
+            let Some((pid, db_path)) = target else {
+                continue;
+            };
+
+            // Sample without holding the lock, walking the DB can take a while
+            let Some(stats) = sampler.sample(pid) else {
+                continue;
+            };
+            let sample = ResourceSample {
+                timestamp: chrono::Utc::now().to_rfc3339(),
+                pid,
+                cpu_percent: stats.cpu_percent,
+                memory_bytes: stats.memory_bytes,
+                threads: stats.threads,
+                open_files: stats.open_files,
+                disk_read_bytes: stats.disk_read_bytes,
+                disk_write_bytes: stats.disk_write_bytes,
+                db_size_bytes: cached_dir_size(&db_path),
+            };
+
+            {
+                let mut monitor_guard = monitor.lock().unwrap();
+                // The node may have been stopped or restarted while sampling
+                if monitor_guard.target.as_ref().map(|(p, _)| *p) != Some(pid) {
+                    continue;
+                }
+                monitor_guard.push(sample.clone());
+            }
+            let _ = app_handle.emit("resource_sample", sample);
+        }
+    });
+}
+
+// Get the recorded resource usage history of the node
+#[tauri::command]
+pub async fn get_resource_history(state: State<'_, AppState>) -> Result<Vec<ResourceSample>, String> {
+    let monitor = state.monitor.lock().unwrap();
+    Ok(monitor.history.iter().cloned().collect())
+}
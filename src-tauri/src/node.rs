@@ -0,0 +1,133 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::paths::{StoragePaths, EXECUTABLE_NAME, NODE_ID};
+
+// How long a node gets to shut down before it is killed
+const STOP_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Build the daemon command line for a node
+pub fn node_command(executable_path: &Path, db_path: &Path, api_port: u16, p2p_port: u16, bootstrap_peers: &[String]) -> Command {
+    let mut cmd = Command::new(executable_path);
+    cmd.arg("daemon")
+       .arg("--api-port")
+       .arg(api_port.to_string())
+       .arg("--db")
+       .arg(db_path)
+       .arg("--p2p-port")
+       .arg(p2p_port.to_string());
+
+    // Pass the configured bootstrap peers to the daemon
+    for peer in bootstrap_peers {
+        cmd.arg("--bootstrap").arg(peer);
+    }
+
+    // Hide console window on Windows
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+// Fail early with a clear message instead of letting the daemon die on a busy port
+pub fn check_ports_free(api_port: u16, p2p_port: u16) -> Result<(), String> {
+    for (name, port) in [("API", api_port), ("P2P", p2p_port)] {
+        if let Err(e) = std::net::TcpListener::bind(("0.0.0.0", port)) {
+            return Err(format!("The {} port {} is already in use: {}", name, port, e));
+        }
+    }
+    Ok(())
+}
+
+// Log file the node output is appended to
+pub fn log_file_path(storage_paths: &StoragePaths) -> PathBuf {
+    storage_paths.logs_dir.join(format!("{}.log", NODE_ID))
+}
+
+// File holding the pid of the running node, shared by the GUI and the command line
+fn pid_file_path(storage_paths: &StoragePaths) -> PathBuf {
+    storage_paths.db_dir.join(format!("{}.pid", NODE_ID))
+}
+
+pub fn write_pid_file(storage_paths: &StoragePaths, pid: u32) -> Result<(), String> {
+    let path = pid_file_path(storage_paths);
+    fs::write(&path, pid.to_string()).map_err(|e| format!("Failed to write {:?}: {}", path, e))
+}
+
+pub fn remove_pid_file(storage_paths: &StoragePaths) {
+    let _ = fs::remove_file(pid_file_path(storage_paths));
+}
+
+// Pid of the running node according to the pid file; stale files are ignored
+pub fn running_pid(storage_paths: &StoragePaths) -> Option<u32> {
+    let pid = fs::read_to_string(pid_file_path(storage_paths)).ok()?.trim().parse().ok()?;
+    is_node_process(pid).then_some(pid)
+}
+
+// Refuse to work on the databases while a node started by `openhash-desktop start` uses them.
+// `managed` tells whether the app runs the node itself, which it can stop and restart on its own.
+pub fn check_not_running_outside(storage_paths: &StoragePaths, managed: bool) -> Result<(), String> {
+    match running_pid(storage_paths) {
+        Some(pid) if !managed => Err(format!(
+            "Node is running outside the app (pid {}), stop it with `openhash-desktop stop` first",
+            pid
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Whether `pid` is a live daemon process, so a reused pid is never mistaken for the node
+#[cfg(target_os = "linux")]
+fn is_node_process(pid: u32) -> bool {
+    // The link gets a " (deleted)" suffix when the binary was replaced by an update
+    fs::read_link(format!("/proc/{}/exe", pid))
+        .ok()
+        .and_then(|exe| exe.file_name().map(|name| name.to_string_lossy().starts_with(EXECUTABLE_NAME)))
+        .unwrap_or(false)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn is_node_process(pid: u32) -> bool {
+    let mut system = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_process(pid)
+        && system
+            .process(pid)
+            .is_some_and(|process| process.name().eq_ignore_ascii_case(EXECUTABLE_NAME))
+}
+
+// Ask the process to exit
+#[cfg(target_os = "linux")]
+fn request_exit(pid: u32, force: bool) -> bool {
+    let signal = if force { libc::SIGKILL } else { libc::SIGTERM };
+    unsafe { libc::kill(pid as libc::pid_t, signal) == 0 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn request_exit(pid: u32, _force: bool) -> bool {
+    let mut system = sysinfo::System::new();
+    let pid = sysinfo::Pid::from_u32(pid);
+    system.refresh_process(pid) && system.process(pid).is_some_and(|process| process.kill())
+}
+
+// Stop a node that is not a child of this process, killing it if it doesn't exit in time
+pub fn terminate(pid: u32) -> Result<(), String> {
+    if !request_exit(pid, false) {
+        return Err(format!("Failed to stop process {}", pid));
+    }
+    let deadline = Instant::now() + STOP_TIMEOUT;
+    while Instant::now() < deadline {
+        if !is_node_process(pid) {
+            return Ok(());
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    request_exit(pid, true);
+    Ok(())
+}
@@ -0,0 +1,34 @@
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::settings::{load_settings, NotificationSettings};
+
+// Kinds of events that can be notified, each enabled separately in the settings
+#[derive(Debug, Clone, Copy)]
+pub enum Category {
+    NodeCrashed,
+    Update,
+    PortConflict,
+    DiskSpace,
+}
+
+impl Category {
+    fn enabled(self, settings: &NotificationSettings) -> bool {
+        match self {
+            Category::NodeCrashed => settings.node_crashed,
+            Category::Update => settings.updates,
+            Category::PortConflict => settings.port_conflict,
+            Category::DiskSpace => settings.disk_space,
+        }
+    }
+}
+
+// Show a desktop notification if its category is enabled.
+// Notifications are best effort, failures are ignored.
+pub fn notify(app_handle: &AppHandle, category: Category, title: &str, body: &str) {
+    let settings = load_settings().map(|settings| settings.notifications).unwrap_or_default();
+    if !category.enabled(&settings) {
+        return;
+    }
+    let _ = app_handle.notification().builder().title(title).body(body).show();
+}
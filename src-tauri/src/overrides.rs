@@ -0,0 +1,179 @@
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use serde::Serialize;
+
+// Settings forced at launch through the environment or the command line.
+// They take precedence over settings.json but are never written to it.
+#[derive(Debug, Default, Clone, Serialize)]
+pub struct Overrides {
+    pub data_dir: Option<PathBuf>,
+    pub bin_dir: Option<PathBuf>,
+    pub logs_dir: Option<PathBuf>,
+    pub api_port: Option<u16>,
+    pub p2p_port: Option<u16>,
+    pub autostart: bool,
+}
+
+static OVERRIDES: OnceLock<Overrides> = OnceLock::new();
+
+pub const USAGE: &str = "Usage: openhash-desktop [--data-dir <path>] [--bin-dir <path>] [--logs-dir <path>]
+                       [--api-port <port>] [--p2p-port <port>] [--start]
+
+--start starts the node once the app is up (--autostart is accepted as well).
+
+Environment variables OPENHASH_DATA_DIR, OPENHASH_BIN_DIR, OPENHASH_LOGS_DIR, OPENHASH_API_PORT,
+OPENHASH_P2P_PORT and OPENHASH_AUTOSTART=1 set the same options; command line arguments win over them.
+Run `openhash-desktop help` for the commands that manage the node without opening a window.";
+
+fn parse_port(name: &str, value: &str) -> Result<u16, String> {
+    value
+        .parse::<u16>()
+        .ok()
+        .filter(|port| *port > 0)
+        .ok_or_else(|| format!("Invalid value {:?} for {}, expected a port between 1 and 65535", value, name))
+}
+
+fn parse_flag(name: &str, value: &str) -> Result<bool, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "1" | "true" | "yes" | "on" => Ok(true),
+        "" | "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("Invalid value {:?} for {}, expected true or false", value, name)),
+    }
+}
+
+impl Overrides {
+    // Read the OPENHASH_* environment variables
+    pub fn from_env() -> Result<Self, String> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let mut overrides = Overrides {
+            data_dir: var("OPENHASH_DATA_DIR").map(PathBuf::from),
+            bin_dir: var("OPENHASH_BIN_DIR").map(PathBuf::from),
+            logs_dir: var("OPENHASH_LOGS_DIR").map(PathBuf::from),
+            ..Default::default()
+        };
+        if let Some(value) = var("OPENHASH_API_PORT") {
+            overrides.api_port = Some(parse_port("OPENHASH_API_PORT", &value)?);
+        }
+        if let Some(value) = var("OPENHASH_P2P_PORT") {
+            overrides.p2p_port = Some(parse_port("OPENHASH_P2P_PORT", &value)?);
+        }
+        if let Some(value) = var("OPENHASH_AUTOSTART") {
+            overrides.autostart = parse_flag("OPENHASH_AUTOSTART", &value)?;
+        }
+        Ok(overrides)
+    }
+
+    // Read the environment, then apply command line arguments (without the program name) on top
+    pub fn from_env_and_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        Self::from_env()?.apply_args(args)
+    }
+
+    // Read command line arguments only, for arguments forwarded from another launch
+    pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Self, String> {
+        Self::default().apply_args(args)
+    }
+
+    // Whether directories or ports are overridden, which only take effect when the app starts
+    pub fn has_launch_options(&self) -> bool {
+        self.data_dir.is_some()
+            || self.bin_dir.is_some()
+            || self.logs_dir.is_some()
+            || self.api_port.is_some()
+            || self.p2p_port.is_some()
+    }
+
+    fn apply_args<I: IntoIterator<Item = String>>(self, args: I) -> Result<Self, String> {
+        let mut overrides = self;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let (name, inline_value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_string(), Some(value.to_string())),
+                None => (arg.clone(), None),
+            };
+            let mut value = || {
+                inline_value
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("Missing value for {}", name))
+            };
+            match name.as_str() {
+                "--data-dir" => overrides.data_dir = Some(PathBuf::from(value()?)),
+                "--bin-dir" => overrides.bin_dir = Some(PathBuf::from(value()?)),
+                "--logs-dir" => overrides.logs_dir = Some(PathBuf::from(value()?)),
+                "--api-port" => overrides.api_port = Some(parse_port("--api-port", &value()?)?),
+                "--p2p-port" => overrides.p2p_port = Some(parse_port("--p2p-port", &value()?)?),
+                "--start" | "--autostart" => overrides.autostart = true,
+                _ => return Err(format!("Unknown argument {:?}\n\n{}", arg, USAGE)),
+            }
+        }
+        Ok(overrides)
+    }
+}
+
+// Install the overrides for this run; only the first call has an effect
+pub fn init(overrides: Overrides) {
+    let _ = OVERRIDES.set(overrides);
+}
+
+pub fn get() -> &'static Overrides {
+    OVERRIDES.get_or_init(Overrides::default)
+}
+
+// Get the overrides the app was launched with
+#[tauri::command]
+pub fn get_overrides() -> Overrides {
+    get().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Result<Overrides, String> {
+        Overrides::from_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_separate_and_inline_values() {
+        let overrides = args(&["--data-dir", "/data", "--api-port=9000", "--p2p-port", "9001", "--start"]).unwrap();
+        assert_eq!(overrides.data_dir, Some(PathBuf::from("/data")));
+        assert_eq!(overrides.api_port, Some(9000));
+        assert_eq!(overrides.p2p_port, Some(9001));
+        assert_eq!(overrides.bin_dir, None);
+        assert!(overrides.autostart);
+        assert!(overrides.has_launch_options());
+    }
+
+    #[test]
+    fn accepts_autostart_alias() {
+        let overrides = args(&["--autostart"]).unwrap();
+        assert!(overrides.autostart);
+        assert!(!overrides.has_launch_options());
+    }
+
+    #[test]
+    fn later_arguments_win() {
+        let overrides = args(&["--logs-dir", "/a", "--logs-dir=/b"]).unwrap();
+        assert_eq!(overrides.logs_dir, Some(PathBuf::from("/b")));
+    }
+
+    #[test]
+    fn arguments_apply_on_top_of_earlier_values() {
+        let base = Overrides {
+            bin_dir: Some(PathBuf::from("/env/bin")),
+            api_port: Some(8000),
+            ..Default::default()
+        };
+        let overrides = base.apply_args(["--api-port".to_string(), "9000".to_string()]).unwrap();
+        assert_eq!(overrides.bin_dir, Some(PathBuf::from("/env/bin")));
+        assert_eq!(overrides.api_port, Some(9000));
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(args(&["--data-dir"]).unwrap_err().contains("Missing value"));
+        assert!(args(&["--api-port", "0"]).is_err());
+        assert!(args(&["--p2p-port=65536"]).is_err());
+        assert!(args(&["--unknown"]).unwrap_err().contains("Unknown argument"));
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+use serde::Serialize;
+
+use crate::overrides;
+use crate::settings::{load_settings, AppSettings};
+
+// File name of the daemon binary in the binaries directory
+pub const EXECUTABLE_NAME: &str = "openhash.exe";
+
+// Id and database subdirectory of the node managed by the app
+pub const NODE_ID: &str = "node1";
+
+// Ids of all nodes managed by the app
+pub const KNOWN_NODES: &[&str] = &[NODE_ID];
+
+fn app_dir(base: Option<PathBuf>, fallback: &str) -> PathBuf {
+    let mut path = base.unwrap_or_else(|| {
+        // Fallback for systems where the platform directory is not available
+        std::env::current_dir().unwrap_or_default().join(fallback)
+    });
+    path.push("OpenHash");
+    path
+}
+
+// Directory that held both the daemon binary and the node databases before they were split
+pub fn legacy_data_dir() -> PathBuf {
+    app_dir(dirs::data_dir(), "data")
+}
+
+// Get the default parent directory of the node databases. It is a sibling of the other
+// default directories, which share the same base on Linux and macOS, so that moving or
+// removing the databases never touches the binary, the logs or the backups.
+pub fn default_db_dir() -> PathBuf {
+    app_dir(dirs::data_dir(), "data").join("data")
+}
+
+// Get the default directory for downloaded binaries
+pub fn default_bin_dir() -> PathBuf {
+    app_dir(dirs::data_local_dir(), "data").join("bin")
+}
+
+// Get the default directory for node log files
+pub fn default_logs_dir() -> PathBuf {
+    app_dir(dirs::data_local_dir(), "data").join("logs")
+}
+
+// Get the default directory for scheduled backups
+pub fn default_backups_dir() -> PathBuf {
+    app_dir(dirs::data_local_dir(), "data").join("backups")
+}
+
+fn configured(path: &Option<String>) -> Option<PathBuf> {
+    path.as_ref().filter(|p| !p.is_empty()).map(PathBuf::from)
+}
+
+// Effective directories after launch overrides, settings and defaults
+#[derive(Debug, Serialize, Clone)]
+pub struct StoragePaths {
+    pub bin_dir: PathBuf,
+    pub db_dir: PathBuf,
+    pub logs_dir: PathBuf,
+}
+
+impl StoragePaths {
+    pub fn resolve(settings: &AppSettings) -> Self {
+        let overrides = overrides::get();
+        Self {
+            bin_dir: overrides
+                .bin_dir
+                .clone()
+                .or_else(|| configured(&settings.bin_dir))
+                .unwrap_or_else(default_bin_dir),
+            db_dir: overrides
+                .data_dir
+                .clone()
+                .or_else(|| configured(&settings.db_dir))
+                .unwrap_or_else(default_db_dir),
+            logs_dir: overrides
+                .logs_dir
+                .clone()
+                .or_else(|| configured(&settings.logs_dir))
+                .unwrap_or_else(default_logs_dir),
+        }
+    }
+
+    pub fn load() -> Result<Self, String> {
+        Ok(Self::resolve(&load_settings()?))
+    }
+
+    pub fn executable_path(&self) -> PathBuf {
+        self.bin_dir.join(EXECUTABLE_NAME)
+    }
+}
+
+// Get the directories used for binaries, databases and logs
+#[tauri::command]
+pub fn get_storage_paths() -> Result<StoragePaths, String> {
+    StoragePaths::load()
+}
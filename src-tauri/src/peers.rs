@@ -0,0 +1,125 @@
+use tauri::{AppHandle, State};
+
+use crate::client;
+use crate::settings::{load_settings, modify_settings};
+use crate::{add_log_entry, AppState};
+
+// Protocols whose value is a single component, e.g. `/ip4/127.0.0.1`
+const MULTIADDR_PROTOCOLS: &[&str] = &[
+    "ip4", "ip6", "dns", "dns4", "dns6", "dnsaddr", "tcp", "udp", "p2p", "ipfs",
+];
+// Protocols that carry no value
+const MULTIADDR_FLAGS: &[&str] = &["quic", "quic-v1", "ws", "wss", "webtransport", "p2p-circuit"];
+
+// Check that an address looks like a valid multiaddr, e.g. `/ip4/1.2.3.4/tcp/4001/p2p/<peer id>`
+pub fn validate_multiaddr(address: &str) -> Result<(), String> {
+    let invalid = |reason: &str| Err(format!("Invalid multiaddr {:?}: {}", address, reason));
+    let Some(rest) = address.strip_prefix('/') else {
+        return invalid("must start with '/'");
+    };
+
+    let mut components = rest.split('/');
+    let mut seen_any = false;
+    while let Some(protocol) = components.next() {
+        seen_any = true;
+        if MULTIADDR_FLAGS.contains(&protocol) {
+            continue;
+        }
+        if !MULTIADDR_PROTOCOLS.contains(&protocol) {
+            return invalid(&format!("unknown protocol {:?}", protocol));
+        }
+        match components.next() {
+            Some(value) if !value.is_empty() => {
+                if matches!(protocol, "tcp" | "udp") && value.parse::<u16>().is_err() {
+                    return invalid(&format!("invalid port {:?}", value));
+                }
+            }
+            _ => return invalid(&format!("missing value for {:?}", protocol)),
+        }
+    }
+
+    if !seen_any {
+        return invalid("empty address");
+    }
+    Ok(())
+}
+
+// Connect the node to a peer by multiaddr
+#[tauri::command]
+pub async fn connect_peer(address: String, state: State<'_, AppState>) -> Result<(), String> {
+    validate_multiaddr(&address)?;
+    client::client_for(&state)?.connect_peer(&address).await?;
+    add_log_entry(&state.logs, &format!("Connected to peer {}", address));
+    Ok(())
+}
+
+// Disconnect the node from a peer by multiaddr
+#[tauri::command]
+pub async fn disconnect_peer(address: String, state: State<'_, AppState>) -> Result<(), String> {
+    validate_multiaddr(&address)?;
+    client::client_for(&state)?.disconnect_peer(&address).await?;
+    add_log_entry(&state.logs, &format!("Disconnected from peer {}", address));
+    Ok(())
+}
+
+// Get the bootstrap peers passed to the node on start
+#[tauri::command]
+pub fn get_bootstrap_peers() -> Result<Vec<String>, String> {
+    Ok(load_settings()?.bootstrap_peers)
+}
+
+// Add a bootstrap peer, used from the next node start
+#[tauri::command]
+pub fn add_bootstrap_peer(app_handle: AppHandle, address: String) -> Result<Vec<String>, String> {
+    validate_multiaddr(&address)?;
+    let settings = modify_settings(&app_handle, |settings| {
+        if !settings.bootstrap_peers.contains(&address) {
+            settings.bootstrap_peers.push(address);
+        }
+        Ok(())
+    })?;
+    Ok(settings.bootstrap_peers)
+}
+
+// Remove a bootstrap peer
+#[tauri::command]
+pub fn remove_bootstrap_peer(app_handle: AppHandle, address: String) -> Result<Vec<String>, String> {
+    let settings = modify_settings(&app_handle, |settings| {
+        settings.bootstrap_peers.retain(|peer| peer != &address);
+        Ok(())
+    })?;
+    Ok(settings.bootstrap_peers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_common_addresses() {
+        for address in [
+            "/ip4/1.2.3.4/tcp/4001",
+            "/ip4/1.2.3.4/tcp/4001/p2p/12D3KooWPeer",
+            "/ip6/::1/udp/4001/quic-v1",
+            "/dns4/bootstrap.example.org/tcp/443/wss",
+            "/ip4/1.2.3.4/tcp/4001/p2p/12D3KooWRelay/p2p-circuit/p2p/12D3KooWPeer",
+        ] {
+            assert!(validate_multiaddr(address).is_ok(), "{}", address);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_addresses() {
+        for address in [
+            "",
+            "/",
+            "ip4/1.2.3.4/tcp/4001",
+            "/ip4/1.2.3.4/tcp",
+            "/ip4//tcp/4001",
+            "/ip4/1.2.3.4/tcp/70000",
+            "/ip4/1.2.3.4/sctp/4001",
+        ] {
+            assert!(validate_multiaddr(address).is_err(), "{}", address);
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::migrate;
+use crate::paths::{legacy_data_dir, KNOWN_NODES};
+use crate::peers::validate_multiaddr;
+use crate::settings::{load_settings, modify_settings, AppSettings};
+
+const PROFILE_VERSION: u32 = 1;
+
+// Configuration of one node in a profile
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ProfileNode {
+    pub id: String,
+    pub api_port: u16,
+    pub p2p_port: u16,
+}
+
+// Portable node setup shared between machines.
+// `data_path` is the database directory relative to the app's data root, which holds both the default
+// database directory and the one older installs were pinned to; paths outside of it are not exported.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NodeProfile {
+    pub version: u32,
+    pub nodes: Vec<ProfileNode>,
+    #[serde(default)]
+    pub data_path: Option<String>,
+    pub update_channel: String,
+    #[serde(default)]
+    pub bootstrap_peers: Vec<String>,
+}
+
+#[derive(Clone, Copy)]
+enum ProfileFormat {
+    Json,
+    Toml,
+}
+
+impl ProfileFormat {
+    fn from_path(path: &Path) -> Result<Self, String> {
+        match path.extension().and_then(|e| e.to_str()).map(|e| e.to_ascii_lowercase()).as_deref() {
+            Some("json") => Ok(ProfileFormat::Json),
+            Some("toml") => Ok(ProfileFormat::Toml),
+            _ => Err(format!("Unsupported profile format for {:?}, use a .json or .toml file", path)),
+        }
+    }
+}
+
+// Express a path relative to the data root, if it lies inside it; the root itself is "."
+fn relative_to_data_dir(path: &str) -> Option<String> {
+    let relative = Path::new(path).strip_prefix(legacy_data_dir()).ok()?;
+    let parts: Vec<_> = relative.components().map(|c| c.as_os_str().to_string_lossy()).collect();
+    if parts.is_empty() {
+        return Some(".".to_string());
+    }
+    Some(parts.join("/"))
+}
+
+// Resolve a profile path against the data root, refusing to escape it
+fn resolve_in_data_dir(relative: &str) -> Result<PathBuf, String> {
+    let mut path = legacy_data_dir();
+    for component in Path::new(relative).components() {
+        match component {
+            Component::Normal(part) => path.push(part),
+            Component::CurDir => {}
+            _ => return Err(format!("Profile data path {:?} must be relative to the data directory", relative)),
+        }
+    }
+    Ok(path)
+}
+
+impl NodeProfile {
+    fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            version: PROFILE_VERSION,
+            nodes: vec![ProfileNode {
+                id: KNOWN_NODES[0].to_string(),
+                api_port: settings.node.api_port,
+                p2p_port: settings.node.p2p_port,
+            }],
+            data_path: settings.db_dir.as_deref().and_then(relative_to_data_dir),
+            update_channel: settings.updater.channel.clone(),
+            bootstrap_peers: settings.bootstrap_peers.clone(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.version != PROFILE_VERSION {
+            return Err(format!("Unsupported profile version {}", self.version));
+        }
+        if self.nodes.is_empty() {
+            return Err("Profile does not contain any node".to_string());
+        }
+        for (index, node) in self.nodes.iter().enumerate() {
+            if !KNOWN_NODES.contains(&node.id.as_str()) {
+                return Err(format!("Unknown node {:?} in profile", node.id));
+            }
+            if self.nodes[..index].iter().any(|other| other.id == node.id) {
+                return Err(format!("Node {:?} appears more than once in profile", node.id));
+            }
+        }
+        if let Some(data_path) = &self.data_path {
+            resolve_in_data_dir(data_path)?;
+        }
+        for peer in &self.bootstrap_peers {
+            validate_multiaddr(peer)?;
+        }
+        Ok(())
+    }
+
+    // Apply the profile on top of the current settings
+    fn apply(&self, settings: &mut AppSettings) -> Result<(), String> {
+        if let Some(node) = self.nodes.iter().find(|node| node.id == KNOWN_NODES[0]) {
+            settings.node.api_port = node.api_port;
+            settings.node.p2p_port = node.p2p_port;
+        }
+        if let Some(data_path) = &self.data_path {
+            settings.db_dir = Some(resolve_in_data_dir(data_path)?.to_string_lossy().into_owned());
+        }
+        settings.updater.channel = self.update_channel.clone();
+        settings.bootstrap_peers = self.bootstrap_peers.clone();
+        Ok(())
+    }
+}
+
+// Write the current node setup to a .json or .toml profile
+#[tauri::command]
+pub fn export_profile(path: String) -> Result<(), String> {
+    let path = PathBuf::from(path);
+    let profile = NodeProfile::from_settings(&load_settings()?);
+    let content = match ProfileFormat::from_path(&path)? {
+        ProfileFormat::Json => serde_json::to_string_pretty(&profile).map_err(|e| e.to_string()),
+        ProfileFormat::Toml => toml::to_string_pretty(&profile).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Failed to serialize profile: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write profile {:?}: {}", path, e))
+}
+
+// Validate a .json or .toml profile and apply it to the settings
+#[tauri::command]
+pub fn import_profile(app_handle: AppHandle, path: String) -> Result<AppSettings, String> {
+    let path = PathBuf::from(path);
+    let format = ProfileFormat::from_path(&path)?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("Failed to read profile {:?}: {}", path, e))?;
+    let profile: NodeProfile = match format {
+        ProfileFormat::Json => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        ProfileFormat::Toml => toml::from_str(&content).map_err(|e| e.to_string()),
+    }
+    .map_err(|e| format!("Invalid profile: {}", e))?;
+
+    profile.validate()?;
+    // Switching directories doesn't move any data, the node would start with an empty database
+    if let Some(data_path) = &profile.data_path {
+        let db_dir = resolve_in_data_dir(data_path)?;
+        if migrate::needs_migration(&db_dir)? {
+            return Err(format!(
+                "The profile uses the data directory {:?}, but the node data is in the current one. \
+                 Move the data there first or remove data_path from the profile.",
+                db_dir
+            ));
+        }
+    }
+    modify_settings(&app_handle, |settings| profile.apply(settings))
+}
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::fs;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tauri::{AppHandle, State};
+
+use crate::backup::{self, BackupResult};
+use crate::node;
+use crate::paths::{StoragePaths, KNOWN_NODES};
+use crate::settings::load_settings;
+use crate::{add_log_entry, start_node_from_settings, stop_node, AppState};
+
+// How long a token from `prepare_reset` stays valid
+const TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+
+// Outstanding reset confirmations by node id
+#[derive(Default)]
+pub struct ResetTokens {
+    pending: HashMap<String, (String, Instant)>,
+}
+
+impl ResetTokens {
+    // Issue a new token for a node, replacing any previous one
+    fn issue(&mut self, node_id: &str) -> Result<String, String> {
+        let token = random_token()?;
+        self.pending.insert(node_id.to_string(), (token.clone(), Instant::now()));
+        Ok(token)
+    }
+
+    // Consume the token for a node; each token can be used only once
+    fn redeem(&mut self, node_id: &str, token: &str) -> Result<(), String> {
+        match self.pending.remove(node_id) {
+            Some((expected, issued)) if expected == token && issued.elapsed() <= TOKEN_LIFETIME => Ok(()),
+            Some((expected, _)) if expected == token => Err("The reset token has expired, request a new one".to_string()),
+            _ => Err("Invalid reset token, call prepare_reset first".to_string()),
+        }
+    }
+}
+
+// Hex encoded 32 bytes from the operating system's secure random number generator
+pub fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 32];
+    getrandom::fill(&mut bytes).map_err(|e| format!("Failed to generate a token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+fn check_node_id(node_id: &str) -> Result<(), String> {
+    if KNOWN_NODES.contains(&node_id) {
+        Ok(())
+    } else {
+        Err(format!("Unknown node {:?}", node_id))
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetConfirmation {
+    node_id: String,
+    token: String,
+    expires_in_secs: u64,
+}
+
+// First step of a node reset: get a short-lived token that `reset_node` must be called with
+#[tauri::command]
+pub fn prepare_reset(state: State<'_, AppState>, node_id: String) -> Result<ResetConfirmation, String> {
+    check_node_id(&node_id)?;
+    let token = state.resets.lock().unwrap().issue(&node_id)?;
+    Ok(ResetConfirmation {
+        node_id,
+        token,
+        expires_in_secs: TOKEN_LIFETIME.as_secs(),
+    })
+}
+
+// Stop the node and delete its database, optionally backing it up first.
+// The node is started again with a fresh database if it was running.
+#[tauri::command]
+pub async fn reset_node(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    node_id: String,
+    token: String,
+    keep_backup: bool,
+) -> Result<Option<BackupResult>, String> {
+    check_node_id(&node_id)?;
+    state.resets.lock().unwrap().redeem(&node_id, &token)?;
+
+    let storage_paths = StoragePaths::load()?;
+    let node_dir = storage_paths.db_dir.join(&node_id);
+    let was_running = *state.is_running.lock().unwrap();
+    node::check_not_running_outside(&storage_paths, was_running)?;
+    if was_running {
+        stop_node(state.clone()).await?;
+    }
+
+    let result = async {
+        let backup = if keep_backup && node_dir.exists() {
+            let dir = backup::backups_dir(&load_settings()?);
+            fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {:?}: {}", dir, e))?;
+            Some(backup::create_backup(&app_handle, &dir).await?)
+        } else {
+            None
+        };
+        if node_dir.exists() {
+            fs::remove_dir_all(&node_dir).map_err(|e| format!("Failed to delete {:?}: {}", node_dir, e))?;
+        }
+        Ok::<_, String>(backup)
+    }
+    .await;
+
+    match &result {
+        Ok(_) => add_log_entry(&state.logs, &format!("Reset node {}, deleted {:?}", node_id, node_dir)),
+        Err(e) => add_log_entry(&state.logs, &format!("Failed to reset node {}: {}", node_id, e)),
+    }
+    if was_running {
+        if let Err(e) = start_node_from_settings(app_handle.clone()).await {
+            add_log_entry(&state.logs, &format!("Failed to restart node: {}", e));
+        }
+    }
+    result
+}
@@ -0,0 +1,642 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use crate::paths;
+use crate::peers::validate_multiaddr;
+
+// Version written by this build of the app. Bump it together with a new entry in `MIGRATIONS`
+// only when existing values have to be rewritten; new fields get `#[serde(default)]` instead.
+pub const CURRENT_SETTINGS_VERSION: u32 = 3;
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+// Why the settings file was last backed up and replaced by defaults. Only the load that moved
+// the file aside sees the error, which may happen before the window opens, so it is kept here
+// until the UI takes it.
+static RECOVERY_ERROR: Mutex<Option<String>> = Mutex::new(None);
+
+// Migrations between settings versions; entry `i` upgrades version `i + 1` to `i + 2`
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+];
+
+// Ports and peers of the managed node
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NodeSettings {
+    pub api_port: u16,
+    pub p2p_port: u16,
+    // Start the node with these settings when the app opens
+    #[serde(default)]
+    pub auto_start: bool,
+}
+
+impl Default for NodeSettings {
+    fn default() -> Self {
+        Self {
+            api_port: 8080,
+            p2p_port: 2000,
+            auto_start: false,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RestartMode {
+    #[default]
+    Never,
+    OnFailure,
+    Always,
+}
+
+// What to do when the node process exits without being stopped from the app
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RestartPolicy {
+    pub mode: RestartMode,
+    pub max_retries: u32,
+    pub delay_secs: u64,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            mode: RestartMode::Never,
+            max_retries: 3,
+            delay_secs: 5,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpdaterSettings {
+    pub check_on_startup: bool,
+    pub channel: String,
+}
+
+impl Default for UpdaterSettings {
+    fn default() -> Self {
+        Self {
+            check_on_startup: true,
+            channel: "stable".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UiSettings {
+    pub theme: String,
+    pub show_logs_on_start: bool,
+    pub log_auto_scroll: bool,
+    // Last size and position of each node's webview window, by node id
+    #[serde(default)]
+    pub webview_windows: HashMap<String, WindowGeometry>,
+}
+
+// Window size and position in logical pixels
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct WindowGeometry {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            theme: "system".to_string(),
+            show_logs_on_start: false,
+            log_auto_scroll: true,
+            webview_windows: HashMap::new(),
+        }
+    }
+}
+
+// Periodic backups of the node database
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BackupSettings {
+    pub enabled: bool,
+    pub interval_hours: u32,
+    // Directory for the archives; unset means the default backups directory
+    pub dir: Option<String>,
+    // Number of scheduled archives to keep
+    pub keep: u32,
+}
+
+impl Default for BackupSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: 24,
+            dir: None,
+            keep: 7,
+        }
+    }
+}
+
+// Disk space thresholds for the node data
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DiskSettings {
+    // Warn when the free space on the data volume drops below this
+    pub warn_free_mb: u64,
+    // Below this the disk is considered critically full
+    pub critical_free_mb: u64,
+    // Warn when a node directory grows beyond this size
+    pub quota_mb: Option<u64>,
+    // Stop the node when the disk is critically full
+    pub auto_stop: bool,
+}
+
+impl Default for DiskSettings {
+    fn default() -> Self {
+        Self {
+            warn_free_mb: 5 * 1024,
+            critical_free_mb: 1024,
+            quota_mb: None,
+            auto_stop: false,
+        }
+    }
+}
+
+// Which events show a desktop notification
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationSettings {
+    pub node_crashed: bool,
+    pub updates: bool,
+    pub port_conflict: bool,
+    pub disk_space: bool,
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            node_crashed: true,
+            updates: true,
+            port_conflict: true,
+            disk_space: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AppSettings {
+    pub version: u32,
+    // Directories for the daemon binary, the node databases and the log files; unset means the default
+    #[serde(default)]
+    pub bin_dir: Option<String>,
+    #[serde(default)]
+    pub db_dir: Option<String>,
+    #[serde(default)]
+    pub logs_dir: Option<String>,
+    #[serde(default)]
+    pub bootstrap_peers: Vec<String>,
+    #[serde(default)]
+    pub node: NodeSettings,
+    #[serde(default)]
+    pub restart_policy: RestartPolicy,
+    #[serde(default)]
+    pub updater: UpdaterSettings,
+    #[serde(default)]
+    pub ui: UiSettings,
+    #[serde(default)]
+    pub backup: BackupSettings,
+    #[serde(default)]
+    pub disk: DiskSettings,
+    #[serde(default)]
+    pub notifications: NotificationSettings,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            version: CURRENT_SETTINGS_VERSION,
+            bin_dir: None,
+            db_dir: None,
+            logs_dir: None,
+            bootstrap_peers: Vec::new(),
+            node: NodeSettings::default(),
+            restart_policy: RestartPolicy::default(),
+            updater: UpdaterSettings::default(),
+            ui: UiSettings::default(),
+            backup: BackupSettings::default(),
+            disk: DiskSettings::default(),
+            notifications: NotificationSettings::default(),
+        }
+    }
+}
+
+const UPDATE_CHANNELS: &[&str] = &["stable", "beta"];
+const THEMES: &[&str] = &["system", "light", "dark"];
+
+impl AppSettings {
+    // Reject settings the app or the node could not run with
+    pub fn validate(&self) -> Result<(), String> {
+        let dirs = [
+            ("binaries", &self.bin_dir),
+            ("database", &self.db_dir),
+            ("logs", &self.logs_dir),
+            ("backups", &self.backup.dir),
+        ];
+        for (name, dir) in dirs {
+            if let Some(dir) = dir.as_deref().filter(|d| !d.is_empty()) {
+                if !std::path::Path::new(dir).is_absolute() {
+                    return Err(format!("The {} directory must be an absolute path", name));
+                }
+            }
+        }
+        if self.node.api_port == 0 || self.node.p2p_port == 0 {
+            return Err("Ports must be between 1 and 65535".to_string());
+        }
+        if self.node.api_port == self.node.p2p_port {
+            return Err("The API port and the P2P port must differ".to_string());
+        }
+        for peer in &self.bootstrap_peers {
+            validate_multiaddr(peer)?;
+        }
+        if self.restart_policy.max_retries > 100 {
+            return Err("Restart retries must be at most 100".to_string());
+        }
+        if self.restart_policy.delay_secs == 0 || self.restart_policy.delay_secs > 3600 {
+            return Err("Restart delay must be between 1 and 3600 seconds".to_string());
+        }
+        if !UPDATE_CHANNELS.contains(&self.updater.channel.as_str()) {
+            return Err(format!("Unknown update channel {:?}", self.updater.channel));
+        }
+        if !THEMES.contains(&self.ui.theme.as_str()) {
+            return Err(format!("Unknown theme {:?}", self.ui.theme));
+        }
+        for geometry in self.ui.webview_windows.values() {
+            let values = [geometry.x, geometry.y, geometry.width, geometry.height];
+            if values.iter().any(|v| !v.is_finite()) || geometry.width < 100.0 || geometry.height < 100.0 {
+                return Err("Invalid webview window geometry".to_string());
+            }
+        }
+        if self.backup.interval_hours == 0 || self.backup.interval_hours > 24 * 365 {
+            return Err("Backup interval must be between 1 hour and 1 year".to_string());
+        }
+        if self.backup.keep == 0 || self.backup.keep > 1000 {
+            return Err("Number of backups to keep must be between 1 and 1000".to_string());
+        }
+        if self.disk.critical_free_mb > self.disk.warn_free_mb {
+            return Err("The critical free space threshold must not exceed the warning threshold".to_string());
+        }
+        if self.disk.quota_mb == Some(0) {
+            return Err("The disk quota must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Version 1 files predate the `version` field and only carried `db_path`
+fn migrate_v1_to_v2(mut value: Value) -> Result<Value, String> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| "Settings file is not a JSON object".to_string())?;
+    object.entry("bootstrap_peers").or_insert_with(|| Value::Array(Vec::new()));
+    object.insert("version".to_string(), Value::from(2));
+    Ok(value)
+}
+
+// Version 3 split `db_path`, which held both the binary and the databases, into separate
+// directories. Both are pinned to the old location so existing installs keep working.
+fn migrate_v2_to_v3(mut value: Value) -> Result<Value, String> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| "Settings file is not a JSON object".to_string())?;
+    let old_dir = object
+        .remove("db_path")
+        .and_then(|path| path.as_str().map(str::to_string))
+        .filter(|path| !path.is_empty())
+        .unwrap_or_else(|| paths::legacy_data_dir().to_string_lossy().into_owned());
+    object.insert("bin_dir".to_string(), Value::from(old_dir.clone()));
+    object.insert("db_dir".to_string(), Value::from(old_dir));
+    object.insert("version".to_string(), Value::from(3));
+    Ok(value)
+}
+
+// Read the version of a parsed settings document, files without one are version 1
+fn settings_version(value: &Value) -> Result<u32, String> {
+    match value.get("version") {
+        None => Ok(1),
+        Some(version) => version
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .filter(|v| *v >= 1)
+            .ok_or_else(|| format!("Invalid settings version {}", version)),
+    }
+}
+
+// Upgrade a parsed settings document from `version` to the current version
+fn migrate(mut value: Value, version: u32) -> Result<AppSettings, String> {
+    for migration in &MIGRATIONS[(version - 1) as usize..] {
+        value = migration(value)?;
+    }
+    serde_json::from_value(value).map_err(|e| e.to_string())
+}
+
+// Directory holding the settings file and its lock
+pub fn get_config_dir() -> Result<PathBuf, String> {
+    let mut config_path = dirs::config_dir().unwrap_or_else(|| {
+        // Fallback for systems where config_dir is not available
+        std::env::current_dir().unwrap_or_default()
+    });
+    config_path.push("OpenHash");
+    fs::create_dir_all(&config_path).map_err(|e| format!("Failed to create config directory: {}", e))?;
+    Ok(config_path)
+}
+
+pub fn get_settings_path() -> Result<PathBuf, String> {
+    Ok(get_config_dir()?.join("settings.json"))
+}
+
+// Exclusive lock on the settings, shared with other running instances of the app.
+// The lock is released when the guard is dropped.
+struct SettingsLock {
+    _file: File,
+}
+
+fn lock_settings() -> Result<SettingsLock, String> {
+    let lock_path = get_config_dir()?.join("settings.lock");
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("Failed to open settings lock: {}", e))?;
+    file.lock().map_err(|e| format!("Failed to lock settings: {}", e))?;
+    Ok(SettingsLock { _file: file })
+}
+
+// Move an unreadable settings file aside so it is not lost when defaults are saved over it
+fn backup_settings_file(settings_path: &Path) -> Result<PathBuf, String> {
+    let timestamp = chrono::Utc::now().format("%Y%m%d%H%M%S");
+    let backup_path = settings_path.with_file_name(format!("settings.{}.bak.json", timestamp));
+    fs::rename(settings_path, &backup_path).map_err(|e| format!("Failed to back up settings file: {}", e))?;
+    Ok(backup_path)
+}
+
+// Settings for a run without a settings file. Installs that never saved their settings
+// keep the binary and the databases in the legacy directory; keep using it if it is there.
+fn initial_settings(settings_path: &Path) -> Result<AppSettings, String> {
+    let legacy_dir = paths::legacy_data_dir();
+    if !legacy_dir.join(paths::EXECUTABLE_NAME).exists() && !legacy_dir.join(paths::NODE_ID).exists() {
+        return Ok(AppSettings::default());
+    }
+    let legacy_dir = legacy_dir.to_string_lossy().into_owned();
+    let settings = AppSettings {
+        bin_dir: Some(legacy_dir.clone()),
+        db_dir: Some(legacy_dir),
+        ..AppSettings::default()
+    };
+    write_settings_locked(settings_path, &settings)?;
+    Ok(settings)
+}
+
+// Read the settings file; the caller must hold the settings lock
+fn read_settings_locked(settings_path: &Path) -> Result<AppSettings, String> {
+    if !settings_path.exists() {
+        return initial_settings(settings_path);
+    }
+
+    let content = fs::read_to_string(settings_path).map_err(|e| format!("Failed to read settings: {}", e))?;
+    let parsed = serde_json::from_str::<Value>(&content)
+        .map_err(|e| e.to_string())
+        .and_then(|value| settings_version(&value).map(|version| (value, version)));
+
+    // Leave files from a newer app version untouched so a downgrade doesn't destroy them
+    if let Ok((_, version)) = &parsed {
+        if *version > CURRENT_SETTINGS_VERSION {
+            return Err(format!(
+                "Settings were written by a newer version of the app (version {}, supported up to {})",
+                version, CURRENT_SETTINGS_VERSION
+            ));
+        }
+    }
+
+    match parsed.and_then(|(value, version)| migrate(value, version).map(|settings| (settings, version))) {
+        Ok((settings, version)) => {
+            if version < CURRENT_SETTINGS_VERSION {
+                write_settings_locked(settings_path, &settings)?;
+            }
+            Ok(settings)
+        }
+        Err(e) => {
+            let backup_path = backup_settings_file(settings_path)?;
+            let error = format!(
+                "Settings could not be loaded ({}). The file was backed up to {:?} and defaults will be used.",
+                e, backup_path
+            );
+            *RECOVERY_ERROR.lock().unwrap() = Some(error.clone());
+            Err(error)
+        }
+    }
+}
+
+// Write the settings through a synced temporary file renamed over the original, so a crash
+// never leaves a truncated file behind. The caller must hold the settings lock.
+fn write_settings_locked(settings_path: &Path, settings: &AppSettings) -> Result<(), String> {
+    let settings = AppSettings {
+        version: CURRENT_SETTINGS_VERSION,
+        ..settings.clone()
+    };
+    let content = serde_json::to_string_pretty(&settings).map_err(|e| format!("Failed to serialize settings: {}", e))?;
+
+    let temp_path = settings_path.with_extension("json.tmp");
+    let mut file = File::create(&temp_path).map_err(|e| format!("Failed to write settings: {}", e))?;
+    file.write_all(content.as_bytes())
+        .and_then(|_| file.sync_all())
+        .map_err(|e| format!("Failed to write settings: {}", e))?;
+    drop(file);
+
+    fs::rename(&temp_path, settings_path).map_err(|e| format!("Failed to replace settings file: {}", e))?;
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Some(dir) = settings_path.parent() {
+        let _ = File::open(dir).and_then(|dir| dir.sync_all());
+    }
+    Ok(())
+}
+
+// Load the settings, migrating older files to the current version.
+// A file that can't be parsed is backed up and reported instead of being silently replaced.
+pub fn load_settings() -> Result<AppSettings, String> {
+    let _lock = lock_settings()?;
+    read_settings_locked(&get_settings_path()?)
+}
+
+pub fn save_settings(settings: &AppSettings) -> Result<(), String> {
+    let _lock = lock_settings()?;
+    write_settings_locked(&get_settings_path()?, settings)
+}
+
+fn emit_settings_changed(app_handle: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    app_handle
+        .emit("settings_changed", settings)
+        .map_err(|e| format!("Failed to emit settings_changed event: {}", e))
+}
+
+// Validate and save new settings, notifying the frontend with `settings_changed`
+pub fn store_settings(app_handle: &AppHandle, settings: &AppSettings) -> Result<(), String> {
+    settings.validate()?;
+    save_settings(settings)?;
+    emit_settings_changed(app_handle, settings)
+}
+
+// Change the settings in place while holding the lock, so concurrent changes are not lost.
+// The result is validated before it is saved and `settings_changed` is emitted.
+pub fn modify_settings<F>(app_handle: &AppHandle, change: F) -> Result<AppSettings, String>
+where
+    F: FnOnce(&mut AppSettings) -> Result<(), String>,
+{
+    let settings = {
+        let _lock = lock_settings()?;
+        let settings_path = get_settings_path()?;
+        let mut settings = read_settings_locked(&settings_path)?;
+        change(&mut settings)?;
+        settings.validate()?;
+        write_settings_locked(&settings_path, &settings)?;
+        settings
+    };
+    emit_settings_changed(app_handle, &settings)?;
+    Ok(settings)
+}
+
+// Get all app and node settings
+#[tauri::command]
+pub fn get_settings() -> Result<AppSettings, String> {
+    load_settings()
+}
+
+// Take the error of a settings file that was backed up and replaced by defaults, if any
+#[tauri::command]
+pub fn take_settings_error() -> Option<String> {
+    RECOVERY_ERROR.lock().unwrap().take()
+}
+
+// Replace all app and node settings
+#[tauri::command]
+pub fn update_settings(app_handle: AppHandle, settings: AppSettings) -> Result<AppSettings, String> {
+    store_settings(&app_handle, &settings)?;
+    load_settings()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    // A fresh directory for one test's settings file
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("openhash-settings-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn migrate_json(value: Value) -> Result<AppSettings, String> {
+        let version = settings_version(&value)?;
+        migrate(value, version)
+    }
+
+    #[test]
+    fn migrates_v1_file() {
+        let settings = migrate_json(json!({ "db_path": "/srv/openhash" })).unwrap();
+        assert_eq!(settings.bin_dir.as_deref(), Some("/srv/openhash"));
+        assert_eq!(settings.db_dir.as_deref(), Some("/srv/openhash"));
+        assert!(settings.bootstrap_peers.is_empty());
+        assert_eq!(settings.node.api_port, 8080);
+    }
+
+    #[test]
+    fn migrates_v1_file_without_db_path_to_legacy_dir() {
+        let settings = migrate_json(json!({ "db_path": "" })).unwrap();
+        let legacy_dir = paths::legacy_data_dir().to_string_lossy().into_owned();
+        assert_eq!(settings.bin_dir, Some(legacy_dir.clone()));
+        assert_eq!(settings.db_dir, Some(legacy_dir));
+    }
+
+    #[test]
+    fn keeps_v2_values() {
+        let settings = migrate_json(json!({
+            "version": 2,
+            "db_path": "/srv/openhash",
+            "bootstrap_peers": ["/ip4/1.2.3.4/tcp/4001"],
+        }))
+        .unwrap();
+        assert_eq!(settings.db_dir.as_deref(), Some("/srv/openhash"));
+        assert_eq!(settings.bootstrap_peers, vec!["/ip4/1.2.3.4/tcp/4001".to_string()]);
+    }
+
+    #[test]
+    fn reads_current_file_with_missing_sections() {
+        let settings = migrate_json(json!({
+            "version": 3,
+            "db_dir": "/data",
+            "node": { "api_port": 9000, "p2p_port": 9001 },
+        }))
+        .unwrap();
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+        assert_eq!(settings.db_dir.as_deref(), Some("/data"));
+        assert_eq!(settings.bin_dir, None);
+        assert_eq!(settings.node.api_port, 9000);
+        assert!(!settings.node.auto_start);
+        assert_eq!(settings.restart_policy.mode, RestartMode::Never);
+    }
+
+    #[test]
+    fn rejects_invalid_versions() {
+        assert!(settings_version(&json!({ "version": 0 })).is_err());
+        assert!(settings_version(&json!({ "version": "3" })).is_err());
+    }
+
+    #[test]
+    fn leaves_newer_file_untouched() {
+        let dir = test_dir("newer");
+        let settings_path = dir.join("settings.json");
+        let content = json!({ "version": 8, "db_dir": "/data" }).to_string();
+        fs::write(&settings_path, &content).unwrap();
+
+        let error = read_settings_locked(&settings_path).unwrap_err();
+        assert!(error.contains("newer version"), "{}", error);
+        assert_eq!(fs::read_to_string(&settings_path).unwrap(), content);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn backs_up_corrupt_file() {
+        let dir = test_dir("corrupt");
+        let settings_path = dir.join("settings.json");
+        fs::write(&settings_path, "{ not json").unwrap();
+
+        let error = read_settings_locked(&settings_path).unwrap_err();
+        assert!(!settings_path.exists());
+        let backups: Vec<PathBuf> = fs::read_dir(&dir).unwrap().map(|entry| entry.unwrap().path()).collect();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), "{ not json");
+        assert_eq!(take_settings_error(), Some(error));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn upgrades_old_file_on_disk() {
+        let dir = test_dir("upgrade");
+        let settings_path = dir.join("settings.json");
+        fs::write(&settings_path, json!({ "db_path": "/srv/openhash" }).to_string()).unwrap();
+
+        read_settings_locked(&settings_path).unwrap();
+        let written: Value = serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+        assert_eq!(written["version"], json!(CURRENT_SETTINGS_VERSION));
+        assert_eq!(written["db_dir"], json!("/srv/openhash"));
+        assert!(written.get("db_path").is_none());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
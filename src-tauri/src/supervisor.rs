@@ -0,0 +1,172 @@
+use std::process::ExitStatus;
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::notify::{self, Category};
+use crate::node;
+use crate::paths::StoragePaths;
+use crate::settings::{load_settings, RestartMode, RestartPolicy};
+use crate::{add_log_entry, restart_node_from_settings, AppState};
+
+// How often the node process is checked for an exit
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+// A node that ran this long before exiting gets the full number of restarts again
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+pub enum RestartDecision {
+    // Restart the node, this is the given attempt since it last ran stably
+    Restart(u32),
+    // The policy wants a restart but the retries are used up
+    GiveUp(u32),
+    // The policy doesn't restart after this exit
+    Stay,
+}
+
+// Apply the restart policy to a node exit, counting the attempts in `attempts`.
+// Shared by the app and the command line supervisor.
+pub fn restart_decision(policy: &RestartPolicy, status: ExitStatus, uptime: Duration, attempts: &mut u32) -> RestartDecision {
+    let restart = match policy.mode {
+        RestartMode::Never => false,
+        RestartMode::OnFailure => !status.success(),
+        RestartMode::Always => true,
+    };
+    if !restart {
+        return RestartDecision::Stay;
+    }
+    if uptime >= STABLE_UPTIME {
+        *attempts = 0;
+    }
+    if *attempts >= policy.max_retries {
+        return RestartDecision::GiveUp(*attempts);
+    }
+    *attempts += 1;
+    RestartDecision::Restart(*attempts)
+}
+
+// Watch the node process `pid` and handle it exiting without `stop_node`:
+// reset the node state, notify the user and restart it according to the restart policy
+pub fn watch_exit(app_handle: AppHandle, pid: u32) {
+    thread::spawn(move || {
+        let started = Instant::now();
+        let state = app_handle.state::<AppState>();
+        let status = loop {
+            thread::sleep(POLL_INTERVAL);
+            let mut process_guard = state.process.lock().unwrap();
+            match process_guard.as_mut() {
+                Some(child) if child.id() == pid => {
+                    if let Ok(Some(status)) = child.try_wait() {
+                        process_guard.take();
+                        break status;
+                    }
+                }
+                // Stopped or replaced from the app
+                _ => return,
+            }
+        };
+
+        *state.is_running.lock().unwrap() = false;
+        state.monitor.lock().unwrap().unwatch();
+        *state.api_port.lock().unwrap() = None;
+        if let Ok(storage_paths) = StoragePaths::load() {
+            node::remove_pid_file(&storage_paths);
+        }
+        add_log_entry(&state.logs, &format!("OpenHash node exited unexpectedly ({})", status));
+        notify::notify(
+            &app_handle,
+            Category::NodeCrashed,
+            "OpenHash node stopped",
+            &format!("The node exited unexpectedly ({})", status),
+        );
+
+        let policy = load_settings().map(|settings| settings.restart_policy).unwrap_or_default();
+        let decision = restart_decision(&policy, status, started.elapsed(), &mut state.restart_attempts.lock().unwrap());
+        let attempt = match decision {
+            RestartDecision::Restart(attempt) => attempt,
+            RestartDecision::GiveUp(attempts) => {
+                add_log_entry(&state.logs, &format!("Not restarting node after {} attempts", attempts));
+                return;
+            }
+            RestartDecision::Stay => return,
+        };
+        add_log_entry(
+            &state.logs,
+            &format!("Restarting node in {}s (attempt {}/{})", policy.delay_secs, attempt, policy.max_retries),
+        );
+        thread::sleep(Duration::from_secs(policy.delay_secs));
+
+        // The node may have been started from the app in the meantime
+        if *state.is_running.lock().unwrap() {
+            return;
+        }
+        if let Err(e) = tauri::async_runtime::block_on(restart_node_from_settings(app_handle.clone())) {
+            add_log_entry(&state.logs, &format!("Failed to restart node: {}", e));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    fn exit_status(code: i32) -> ExitStatus {
+        std::os::unix::process::ExitStatusExt::from_raw(code << 8)
+    }
+
+    #[cfg(windows)]
+    fn exit_status(code: i32) -> ExitStatus {
+        std::os::windows::process::ExitStatusExt::from_raw(code as u32)
+    }
+
+    fn policy(mode: RestartMode, max_retries: u32) -> RestartPolicy {
+        RestartPolicy {
+            mode,
+            max_retries,
+            delay_secs: 0,
+        }
+    }
+
+    const SHORT: Duration = Duration::from_secs(1);
+
+    #[test]
+    fn never_stays_down() {
+        let mut attempts = 0;
+        let decision = restart_decision(&policy(RestartMode::Never, 3), exit_status(1), SHORT, &mut attempts);
+        assert!(matches!(decision, RestartDecision::Stay));
+        assert_eq!(attempts, 0);
+    }
+
+    #[test]
+    fn on_failure_ignores_clean_exits() {
+        let policy = policy(RestartMode::OnFailure, 3);
+        let mut attempts = 0;
+        assert!(matches!(restart_decision(&policy, exit_status(0), SHORT, &mut attempts), RestartDecision::Stay));
+        assert!(matches!(restart_decision(&policy, exit_status(1), SHORT, &mut attempts), RestartDecision::Restart(1)));
+    }
+
+    #[test]
+    fn always_restarts_clean_exits() {
+        let mut attempts = 0;
+        let decision = restart_decision(&policy(RestartMode::Always, 3), exit_status(0), SHORT, &mut attempts);
+        assert!(matches!(decision, RestartDecision::Restart(1)));
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let policy = policy(RestartMode::OnFailure, 2);
+        let mut attempts = 0;
+        assert!(matches!(restart_decision(&policy, exit_status(1), SHORT, &mut attempts), RestartDecision::Restart(1)));
+        assert!(matches!(restart_decision(&policy, exit_status(1), SHORT, &mut attempts), RestartDecision::Restart(2)));
+        assert!(matches!(restart_decision(&policy, exit_status(1), SHORT, &mut attempts), RestartDecision::GiveUp(2)));
+    }
+
+    #[test]
+    fn stable_run_resets_attempts() {
+        let policy = policy(RestartMode::OnFailure, 2);
+        let mut attempts = 2;
+        let decision = restart_decision(&policy, exit_status(1), STABLE_UPTIME, &mut attempts);
+        assert!(matches!(decision, RestartDecision::Restart(1)));
+    }
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, State};
+
+use crate::AppState;
+
+// Cancellation flags of the uploads and fetches currently in flight
+#[derive(Default)]
+pub struct TransferRegistry {
+    active: HashMap<String, Arc<AtomicBool>>,
+}
+
+impl TransferRegistry {
+    // Register a new transfer, returning its cancellation flag
+    pub fn begin(&mut self, transfer_id: &str) -> Result<Arc<AtomicBool>, String> {
+        if self.active.contains_key(transfer_id) {
+            return Err(format!("Transfer {} is already in progress", transfer_id));
+        }
+        let cancelled = Arc::new(AtomicBool::new(false));
+        self.active.insert(transfer_id.to_string(), Arc::clone(&cancelled));
+        Ok(cancelled)
+    }
+
+    pub fn cancel(&mut self, transfer_id: &str) -> bool {
+        match self.active.get(transfer_id) {
+            Some(cancelled) => {
+                cancelled.store(true, Ordering::SeqCst);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn finish(&mut self, transfer_id: &str) {
+        self.active.remove(transfer_id);
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    transfer_id: String,
+    current: u64,
+    total: u64,
+}
+
+// Shared byte counter of a transfer which emits progress events as it advances
+#[derive(Clone)]
+pub struct ProgressTracker {
+    app_handle: AppHandle,
+    event: &'static str,
+    transfer_id: String,
+    current: Arc<AtomicU64>,
+    total: u64,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl ProgressTracker {
+    pub fn new(
+        app_handle: AppHandle,
+        event: &'static str,
+        transfer_id: &str,
+        current: u64,
+        total: u64,
+        cancelled: Arc<AtomicBool>,
+    ) -> Self {
+        Self {
+            app_handle,
+            event,
+            transfer_id: transfer_id.to_string(),
+            current: Arc::new(AtomicU64::new(current)),
+            total,
+            cancelled,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+
+    pub fn advance(&self, bytes: u64) {
+        let current = self.current.fetch_add(bytes, Ordering::SeqCst) + bytes;
+        let _ = self.app_handle.emit(self.event, TransferProgress {
+            transfer_id: self.transfer_id.clone(),
+            current,
+            total: self.total,
+        });
+    }
+}
+
+// Cancel an upload or fetch in progress
+#[tauri::command]
+pub async fn cancel_transfer(transfer_id: String, state: State<'_, AppState>) -> Result<bool, String> {
+    Ok(state.transfers.lock().unwrap().cancel(&transfer_id))
+}
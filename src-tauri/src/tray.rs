@@ -0,0 +1,175 @@
+use std::thread;
+use std::time::Duration;
+use tauri::image::Image;
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Window, WindowEvent, Wry};
+
+use crate::paths::NODE_ID;
+use crate::webview::open_webview;
+use crate::{add_log_entry, start_node_from_settings, stop_node, AppState};
+
+const TRAY_ID: &str = "main";
+
+// How often the node state is checked to refresh the tray
+const REFRESH_INTERVAL: Duration = Duration::from_secs(1);
+
+// Menu items whose text or availability follows the node state
+struct TrayItems {
+    status: MenuItem<Wry>,
+    start: MenuItem<Wry>,
+    stop: MenuItem<Wry>,
+    restart: MenuItem<Wry>,
+    dashboard: MenuItem<Wry>,
+}
+
+// Grey version of the app icon shown while the node is stopped
+fn stopped_icon(icon: &Image<'_>) -> Image<'static> {
+    let rgba = icon
+        .rgba()
+        .chunks_exact(4)
+        .flat_map(|pixel| {
+            let grey = ((pixel[0] as u32 * 30 + pixel[1] as u32 * 59 + pixel[2] as u32 * 11) / 100) as u8;
+            [grey, grey, grey, pixel[3] / 2]
+        })
+        .collect();
+    Image::new_owned(rgba, icon.width(), icon.height())
+}
+
+pub fn show_main_window(app_handle: &AppHandle) {
+    if let Some(window) = app_handle.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+fn handle_menu_event(app_handle: &AppHandle, event: MenuEvent) {
+    let app_handle = app_handle.clone();
+    match event.id().as_ref() {
+        "start" => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = start_node_from_settings(app_handle.clone()).await {
+                    add_log_entry(&app_handle.state::<AppState>().logs, &format!("Failed to start node: {}", e));
+                }
+            });
+        }
+        "stop" => {
+            tauri::async_runtime::spawn(async move {
+                let _ = stop_node(app_handle.state()).await;
+            });
+        }
+        "restart" => {
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                if *state.is_running.lock().unwrap() {
+                    let _ = stop_node(state.clone()).await;
+                }
+                if let Err(e) = start_node_from_settings(app_handle.clone()).await {
+                    add_log_entry(&state.logs, &format!("Failed to restart node: {}", e));
+                }
+            });
+        }
+        "dashboard" => {
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = open_webview(app_handle.clone(), app_handle.state(), NODE_ID.to_string()).await {
+                    add_log_entry(&app_handle.state::<AppState>().logs, &e);
+                }
+            });
+        }
+        "logs" => {
+            show_main_window(&app_handle);
+            let _ = app_handle.emit("show_logs", ());
+        }
+        "show" => show_main_window(&app_handle),
+        "quit" => {
+            tauri::async_runtime::spawn(async move {
+                let state = app_handle.state::<AppState>();
+                if *state.is_running.lock().unwrap() {
+                    let _ = stop_node(state.clone()).await;
+                }
+                app_handle.exit(0);
+            });
+        }
+        _ => {}
+    }
+}
+
+// Bring the tray state in line with the node state
+fn refresh(app_handle: &AppHandle, items: &TrayItems, running: bool, icons: &(Image<'static>, Image<'static>)) {
+    let status = if running { "Node: running" } else { "Node: stopped" };
+    let _ = items.status.set_text(status);
+    let _ = items.start.set_enabled(!running);
+    let _ = items.stop.set_enabled(running);
+    let _ = items.restart.set_enabled(running);
+    let _ = items.dashboard.set_enabled(running);
+    if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+        let _ = tray.set_icon(Some(if running { icons.0.clone() } else { icons.1.clone() }));
+        let _ = tray.set_tooltip(Some(format!("OpenHash - {}", status)));
+    }
+    let _ = app_handle.emit("node_status", running);
+}
+
+// Create the tray icon and keep it updated with the node state
+pub fn setup(app_handle: &AppHandle) -> tauri::Result<()> {
+    let items = TrayItems {
+        status: MenuItem::with_id(app_handle, "status", "Node: stopped", false, None::<&str>)?,
+        start: MenuItem::with_id(app_handle, "start", "Start", true, None::<&str>)?,
+        stop: MenuItem::with_id(app_handle, "stop", "Stop", false, None::<&str>)?,
+        restart: MenuItem::with_id(app_handle, "restart", "Restart", false, None::<&str>)?,
+        dashboard: MenuItem::with_id(app_handle, "dashboard", "Open Dashboard", false, None::<&str>)?,
+    };
+    let menu = Menu::with_items(
+        app_handle,
+        &[
+            &items.status,
+            &PredefinedMenuItem::separator(app_handle)?,
+            &items.start,
+            &items.stop,
+            &items.restart,
+            &PredefinedMenuItem::separator(app_handle)?,
+            &items.dashboard,
+            &MenuItem::with_id(app_handle, "logs", "View Logs", true, None::<&str>)?,
+            &MenuItem::with_id(app_handle, "show", "Show Window", true, None::<&str>)?,
+            &PredefinedMenuItem::separator(app_handle)?,
+            &MenuItem::with_id(app_handle, "quit", "Quit", true, None::<&str>)?,
+        ],
+    )?;
+
+    let icon = app_handle
+        .default_window_icon()
+        .cloned()
+        .ok_or_else(|| tauri::Error::AssetNotFound("default window icon".to_string()))?;
+    let stopped = stopped_icon(&icon);
+    let icons = (icon.to_owned(), stopped);
+    TrayIconBuilder::with_id(TRAY_ID)
+        .icon(icons.1.clone())
+        .tooltip("OpenHash - Node: stopped")
+        .menu(&menu)
+        .on_menu_event(handle_menu_event)
+        .build(app_handle)?;
+
+    let app_handle = app_handle.clone();
+    thread::spawn(move || {
+        let mut last_running = None;
+        loop {
+            let running = *app_handle.state::<AppState>().is_running.lock().unwrap();
+            if last_running != Some(running) {
+                refresh(&app_handle, &items, running, &icons);
+                last_running = Some(running);
+            }
+            thread::sleep(REFRESH_INTERVAL);
+        }
+    });
+    Ok(())
+}
+
+// Hide the main window instead of closing it while the tray icon is available
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if let WindowEvent::CloseRequested { api, .. } = event {
+        if window.label() == "main" && window.app_handle().tray_by_id(TRAY_ID).is_some() {
+            api.prevent_close();
+            let _ = window.hide();
+        }
+    }
+}
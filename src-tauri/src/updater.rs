@@ -0,0 +1,126 @@
+use std::fs;
+use serde::{Deserialize, Serialize};
+
+use crate::download;
+use crate::paths::{StoragePaths, EXECUTABLE_NAME};
+
+const GITHUB_API_URL: &str = "https://api.github.com/repos/nnlgsakib/open-hash-db/releases/latest";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitHubRelease {
+    tag_name: String,
+    assets: Vec<GitHubAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitHubAsset {
+    name: String,
+    browser_download_url: String,
+}
+
+// Result of an update run
+pub enum UpdateOutcome {
+    UpToDate(String),
+    Installed(String),
+}
+
+// Download the latest daemon release into the binaries directory, resuming a partial download.
+// Shared by the GUI and the command line; `log` receives progress messages, `on_download` is
+// called with the release tag before the download starts and `on_progress` after every chunk.
+pub async fn update_executable<D, P>(
+    storage_paths: &StoragePaths,
+    log: &(dyn Fn(&str) + Sync),
+    on_download: D,
+    on_progress: P,
+) -> Result<UpdateOutcome, String>
+where
+    D: FnOnce(&str),
+    P: FnMut(u64, u64) -> Result<(), String>,
+{
+    log("Checking for updates...");
+
+    // Fetch the latest release information
+    let client = reqwest::Client::new();
+    let response = client
+        .get(GITHUB_API_URL)
+        .header("User-Agent", "OpenHash-Wrapper")
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch release info: {}", e))?;
+
+    if !response.status().is_success() {
+        return Err("Failed to fetch release information from GitHub".to_string());
+    }
+
+    let release: GitHubRelease = response
+        .json()
+        .await
+        .map_err(|e| format!("Failed to parse release info: {}", e))?;
+
+    log(&format!("Found release: {}", release.tag_name));
+
+    // Find the openhash.exe asset
+    let asset = release
+        .assets
+        .iter()
+        .find(|asset| asset.name == EXECUTABLE_NAME)
+        .ok_or_else(|| format!("{} not found in release assets", EXECUTABLE_NAME))?;
+
+    // Determine the executable path
+    fs::create_dir_all(&storage_paths.bin_dir).map_err(|e| format!("Failed to create binaries directory: {}", e))?;
+    let executable_path = storage_paths.executable_path();
+    let mut downloaded_bytes: u64 = 0;
+
+    // Get total size from HEAD request first
+    let head_response = client
+        .head(&asset.browser_download_url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to get file size: {}", e))?;
+    let total_size = head_response.content_length().unwrap_or(0);
+
+    // Check if a partial file exists and get its size for resuming
+    if executable_path.exists() {
+        let metadata = fs::metadata(&executable_path)
+            .map_err(|e| format!("Failed to get metadata for existing file: {}", e))?;
+        downloaded_bytes = metadata.len();
+        if total_size > 0 && downloaded_bytes == total_size {
+            log("openhash.exe is already up to date.");
+            return Ok(UpdateOutcome::UpToDate(release.tag_name));
+        } else if total_size > 0 && downloaded_bytes < total_size {
+            log(&format!("Resuming download from {} bytes.", downloaded_bytes));
+        } else if downloaded_bytes > 0 {
+            log("Could not verify file size. Restarting download.");
+            fs::remove_file(&executable_path).map_err(|e| format!("Failed to remove existing file: {}", e))?;
+            downloaded_bytes = 0;
+        }
+    }
+
+    log(&format!("Downloading openhash.exe to {:?}...", executable_path));
+    on_download(&release.tag_name);
+
+    // Download the executable with progress and resumability
+    download::download_to_file(
+        client.get(&asset.browser_download_url),
+        &executable_path,
+        downloaded_bytes,
+        total_size,
+        on_progress,
+    )
+    .await?;
+
+    // Make it executable on Unix systems
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(&executable_path)
+            .map_err(|e| format!("Failed to get file metadata: {}", e))?
+            .permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(&executable_path, perms)
+            .map_err(|e| format!("Failed to set executable permissions: {}", e))?;
+    }
+
+    log("Download completed successfully");
+    Ok(UpdateOutcome::Installed(release.tag_name))
+}
@@ -0,0 +1,93 @@
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use futures_util::Stream;
+use tauri::{AppHandle, State};
+use tokio::io::AsyncReadExt;
+
+use crate::client::{self, AddResponse};
+use crate::fsutil::{collect_files, FoundFile};
+use crate::transfer::ProgressTracker;
+use crate::{add_log_entry, AppState};
+
+const CHUNK_SIZE: usize = 64 * 1024;
+
+// Stream a file in chunks, reporting progress and honouring cancellation
+fn file_stream(path: PathBuf, tracker: ProgressTracker) -> impl Stream<Item = io::Result<Vec<u8>>> + Send + Sync + 'static {
+    futures_util::stream::try_unfold((None, path, tracker), |(file, path, tracker)| async move {
+        let mut file = match file {
+            Some(file) => file,
+            None => tokio::fs::File::open(&path).await?,
+        };
+        if tracker.is_cancelled() {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "Transfer cancelled"));
+        }
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(None);
+        }
+        buffer.truncate(read);
+        tracker.advance(read as u64);
+        Ok(Some((buffer, (Some(file), path, tracker))))
+    })
+}
+
+fn file_part(file: &FoundFile, tracker: &ProgressTracker) -> reqwest::multipart::Part {
+    let body = reqwest::Body::wrap_stream(file_stream(file.path.clone(), tracker.clone()));
+    reqwest::multipart::Part::stream_with_length(body, file.size).file_name(file.relative.clone())
+}
+
+// Add a file or directory to the node, emitting `upload_progress` events
+#[tauri::command]
+pub async fn add_path(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    transfer_id: String,
+) -> Result<AddResponse, String> {
+    let client = client::client_for(&state)?;
+    let root = PathBuf::from(&path);
+    let metadata = fs::metadata(&root).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+
+    let mut files = Vec::new();
+    if metadata.is_dir() {
+        collect_files(&root, &root, &mut files).map_err(|e| format!("Failed to read directory {}: {}", path, e))?;
+    } else {
+        let name = root
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.clone());
+        files.push(FoundFile {
+            path: root.clone(),
+            relative: name,
+            size: metadata.len(),
+        });
+    }
+    let total: u64 = files.iter().map(|f| f.size).sum();
+
+    let cancelled = state.transfers.lock().unwrap().begin(&transfer_id)?;
+    let tracker = ProgressTracker::new(app_handle, "upload_progress", &transfer_id, 0, total, cancelled);
+    add_log_entry(&state.logs, &format!("Adding {} ({} files, {} bytes)", path, files.len(), total));
+
+    let form = files
+        .iter()
+        .fold(reqwest::multipart::Form::new(), |form, file| form.part("file", file_part(file, &tracker)));
+    let result = client.upload(form, metadata.is_dir()).await;
+    state.transfers.lock().unwrap().finish(&transfer_id);
+
+    match result {
+        Ok(response) => {
+            add_log_entry(&state.logs, &format!("Added {} as {}", path, response.hash));
+            Ok(response)
+        }
+        Err(_) if tracker.is_cancelled() => {
+            add_log_entry(&state.logs, &format!("Cancelled adding {}", path));
+            Err("Upload cancelled".to_string())
+        }
+        Err(e) => {
+            add_log_entry(&state.logs, &e);
+            Err(e)
+        }
+    }
+}
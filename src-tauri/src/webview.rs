@@ -0,0 +1,109 @@
+use tauri::{AppHandle, Manager, State, Url, WebviewUrl, WebviewWindowBuilder, Window, WindowEvent};
+
+use crate::client::OpenHashClient;
+use crate::dashboard;
+use crate::paths::KNOWN_NODES;
+use crate::settings::{load_settings, modify_settings, WindowGeometry};
+use crate::{add_log_entry, AppState};
+
+const LABEL_PREFIX: &str = "webview_";
+
+fn window_label(node_id: &str) -> String {
+    format!("{}{}", LABEL_PREFIX, node_id)
+}
+
+// The node's own web interface if it has one, the built-in dashboard otherwise
+async fn interface_url(state: &AppState, node_id: &str) -> Result<Url, String> {
+    if !KNOWN_NODES.contains(&node_id) {
+        return Err(format!("Unknown node {:?}", node_id));
+    }
+    let port = *state.api_port.lock().unwrap();
+    if let Some(port) = port {
+        if OpenHashClient::new(port).serves_html().await {
+            return format!("http://127.0.0.1:{}/", port)
+                .parse()
+                .map_err(|e| format!("Invalid webview URL for port {}: {}", port, e));
+        }
+    }
+    Ok(dashboard::url())
+}
+
+// Custom schemes have opaque origins, so compare scheme, host and port directly
+fn same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme() && a.host_str() == b.host_str() && a.port_or_known_default() == b.port_or_known_default()
+}
+
+// Open the web interface of a node, or focus its window if it is already open
+#[tauri::command]
+pub async fn open_webview(app_handle: AppHandle, state: State<'_, AppState>, node_id: String) -> Result<(), String> {
+    let label = window_label(&node_id);
+    if let Some(window) = app_handle.get_webview_window(&label) {
+        let _ = window.unminimize();
+        window.show().map_err(|e| format!("Failed to show webview window: {}", e))?;
+        return window.set_focus().map_err(|e| format!("Failed to focus webview window: {}", e));
+    }
+
+    let url = interface_url(&state, &node_id).await?;
+
+    // Keep the window on the node's own origin, external links are not followed
+    let origin = url.clone();
+    let logs = state.logs.clone();
+    let mut builder = WebviewWindowBuilder::new(&app_handle, &label, WebviewUrl::External(url))
+        .title(format!("OpenHash - {}", node_id))
+        .inner_size(800.0, 600.0)
+        .on_navigation(move |target| {
+            let allowed = same_origin(target, &origin);
+            if !allowed {
+                add_log_entry(&logs, &format!("Blocked webview navigation to {}", target));
+            }
+            allowed
+        });
+    let geometry = load_settings()
+        .ok()
+        .and_then(|settings| settings.ui.webview_windows.get(&node_id).copied());
+    if let Some(geometry) = geometry {
+        builder = builder
+            .inner_size(geometry.width, geometry.height)
+            .position(geometry.x, geometry.y);
+    }
+    let webview_window = builder
+        .build()
+        .map_err(|e| format!("Failed to create webview window: {}", e))?;
+
+    webview_window.set_focus().map_err(|e| format!("Failed to focus webview window: {}", e))?;
+    Ok(())
+}
+
+// Current size and position of a window in logical pixels
+fn window_geometry(window: &Window) -> tauri::Result<WindowGeometry> {
+    let scale = window.scale_factor()?;
+    let position = window.outer_position()?.to_logical::<f64>(scale);
+    let size = window.inner_size()?.to_logical::<f64>(scale);
+    Ok(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width,
+        height: size.height,
+    })
+}
+
+// Remember the size and position of a node's webview window when it is closed
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    let WindowEvent::CloseRequested { .. } = event else {
+        return;
+    };
+    let Some(node_id) = window.label().strip_prefix(LABEL_PREFIX) else {
+        return;
+    };
+    // Minimized windows report a bogus position and size
+    if window.is_minimized().unwrap_or(false) {
+        return;
+    }
+    if let Ok(geometry) = window_geometry(window) {
+        let node_id = node_id.to_string();
+        let _ = modify_settings(window.app_handle(), |settings| {
+            settings.ui.webview_windows.insert(node_id, geometry);
+            Ok(())
+        });
+    }
+}
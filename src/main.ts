@@ -1,6 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { listen } from "@tauri-apps/api/event";
-import { open } from "@tauri-apps/plugin-dialog";
+import { ask, open } from "@tauri-apps/plugin-dialog";
 
 // DOM elements
 let dbPathEl: HTMLInputElement | null;
@@ -25,12 +25,18 @@ let mainContainerEl: HTMLElement | null;
 let webviewContainerEl: HTMLElement | null;
 let webviewIframeEl: HTMLIFrameElement | null;
 let backBtnEl: HTMLButtonElement | null;
+let linkSectionEl: HTMLElement | null;
+let linkHashEl: HTMLElement | null;
+let linkFetchBtnEl: HTMLButtonElement | null;
+let linkPinBtnEl: HTMLButtonElement | null;
+let linkDismissBtnEl: HTMLButtonElement | null;
 
 // Application state
 let isRunning = false;
 let isUpdating = false;
 let logsVisible = false;
 let logUpdateInterval: number | null = null;
+let pendingLink: DeepLink | null = null;
 
 // Initialize the application
 async function initApp() {
@@ -38,6 +44,9 @@ async function initApp() {
     // Set default database path
     await updateDbPath();
 
+    // Restore the ports used last time
+    await loadNodeSettings();
+
     // Check if openhash.exe exists in the specified path
     const hasExecutable = await invoke("check_executable_exists", {
       dbPath: dbPathEl?.value,
@@ -48,6 +57,12 @@ async function initApp() {
     } else {
       updateInfoMessage("Ready to start OpenHash node.");
     }
+
+    // Settings that could not be read were backed up and replaced by defaults
+    const settingsError = await invoke<string | null>("take_settings_error");
+    if (settingsError) {
+      updateInfoMessage(settingsError);
+    }
     
     // Listen for download events
     await listen<DownloadProgress>("download_progress", (event) => {
@@ -59,8 +74,33 @@ async function initApp() {
       updateProcessStatus(); // Re-check status after download
     });
 
+    // The node can also be started and stopped from the tray menu
+    await listen<boolean>("node_status", (event) => {
+      isRunning = event.payload;
+      updateButtonStates();
+      if (isRunning && logsVisible) {
+        startLogMonitoring();
+      } else if (!isRunning) {
+        stopLogMonitoring();
+      }
+    });
+    await listen("show_logs", () => {
+      if (!logsVisible) {
+        toggleLogs();
+      }
+    });
+
+    // openhash:// links opened while the app runs
+    await listen("deep_link", handleDeepLink);
+    await listen<string>("deep_link_error", (event) => {
+      updateInfoMessage(event.payload);
+    });
+
     // Check process status
     await updateProcessStatus();
+
+    // A link the app was launched with
+    await handleDeepLink();
   } catch (error) {
     console.error("Failed to initialize app:", error);
     updateInfoMessage("Failed to initialize application.");
@@ -81,6 +121,20 @@ async function updateDbPath() {
   }
 }
 
+// Fills the port inputs from the persisted settings
+async function loadNodeSettings() {
+  try {
+    const settings = await invoke<AppSettings>("get_settings");
+    const overrides = await invoke<Overrides>("get_overrides");
+    // Ports forced at launch win over the stored ones
+    if (apiPortEl) apiPortEl.value = (overrides.api_port ?? settings.node.api_port).toString();
+    if (p2pPortEl) p2pPortEl.value = (overrides.p2p_port ?? settings.node.p2p_port).toString();
+  } catch (error) {
+    console.error("Failed to load settings:", error);
+    updateInfoMessage(`Failed to load settings: ${error}`);
+  }
+}
+
 // Opens a dialog to select a new data path
 async function selectCustomPath() {
   try {
@@ -91,16 +145,44 @@ async function selectCustomPath() {
     });
 
     if (typeof selected === 'string' && selected.trim() !== '') {
-      await invoke("set_custom_data_path", { path: selected });
+      // Switching away from the node data would start the node with an empty database
+      if (await invoke<boolean>("data_dir_needs_migration", { path: selected })) {
+        const move = await ask(
+          `Move the node data to ${selected}? The node is stopped while the data is copied and verified, then the old copy is removed.`,
+          { title: "Move Node Data", okLabel: "Move", cancelLabel: "Cancel" },
+        );
+        if (!move) return;
+        updateInfoMessage(`Moving node data to ${selected}...`);
+        await invoke("migrate_data_dir", { newPath: selected, removeOld: true });
+      } else {
+        await invoke("set_custom_data_path", { path: selected });
+      }
       await updateDbPath(); // Refresh the displayed path
       updateInfoMessage(`Data path set to: ${selected}`);
     }
   } catch (error) {
     console.error("Failed to select custom path:", error);
-    updateInfoMessage("Failed to set custom data path.");
+    updateInfoMessage(`Failed to set custom data path: ${error}`);
   }
 }
 
+interface AppSettings {
+  node: {
+    api_port: number;
+    p2p_port: number;
+  };
+}
+
+interface Overrides {
+  api_port: number | null;
+  p2p_port: number | null;
+}
+
+interface DeepLink {
+  hash: string;
+  action: "ask" | "fetch" | "pin";
+}
+
 interface DownloadProgress {
   current: number;
   total: number;
@@ -368,6 +450,73 @@ async function openWebview() {
   webviewContainerEl.style.display = "flex";
 }
 
+// Shows the hash of an opened openhash:// link with the choice to fetch or pin it
+async function handleDeepLink() {
+  try {
+    const link = await invoke<DeepLink | null>("take_deep_link");
+    if (!link || !linkSectionEl || !linkHashEl) return;
+
+    pendingLink = link;
+    linkHashEl.textContent = link.hash;
+    linkSectionEl.style.display = "flex";
+    // A link can suggest an action, the user still confirms it
+    if (link.action === "pin") {
+      linkPinBtnEl?.focus();
+    } else {
+      linkFetchBtnEl?.focus();
+    }
+  } catch (error) {
+    console.error("Failed to handle link:", error);
+    updateInfoMessage(`Failed to handle link: ${error}`);
+  }
+}
+
+function dismissLink() {
+  pendingLink = null;
+  if (linkSectionEl) linkSectionEl.style.display = "none";
+}
+
+// Fetches the content of the opened link into a folder picked by the user
+async function fetchLink() {
+  const link = pendingLink;
+  if (!link) return;
+  try {
+    const folder = await open({
+      directory: true,
+      multiple: false,
+      title: "Select a Folder for the Content",
+    });
+    if (typeof folder !== "string" || folder.trim() === "") return;
+
+    dismissLink();
+    const dest = `${folder}/${link.hash}`;
+    updateInfoMessage(`Fetching ${link.hash}...`);
+    await invoke("fetch_by_hash", {
+      hash: link.hash,
+      dest,
+      transferId: `link-${link.hash}-${Date.now()}`,
+    });
+    updateInfoMessage(`Fetched ${link.hash} to ${dest}.`);
+  } catch (error) {
+    console.error("Failed to fetch link:", error);
+    updateInfoMessage(`Failed to fetch ${link.hash}: ${error}`);
+  }
+}
+
+// Pins the content of the opened link on the node
+async function pinLink() {
+  const link = pendingLink;
+  if (!link) return;
+  dismissLink();
+  try {
+    await invoke("pin_object", { hash: link.hash });
+    updateInfoMessage(`Pinned ${link.hash}.`);
+  } catch (error) {
+    console.error("Failed to pin link:", error);
+    updateInfoMessage(`Failed to pin ${link.hash}: ${error}`);
+  }
+}
+
 // Close webview
 function closeWebview() {
   if (!mainContainerEl || !webviewContainerEl || !webviewIframeEl) return;
@@ -402,6 +551,11 @@ window.addEventListener("DOMContentLoaded", () => {
   webviewContainerEl = document.querySelector("#webview-container");
   webviewIframeEl = document.querySelector("#webview-iframe");
   backBtnEl = document.querySelector("#back-btn");
+  linkSectionEl = document.querySelector("#link-section");
+  linkHashEl = document.querySelector("#link-hash");
+  linkFetchBtnEl = document.querySelector("#link-fetch-btn");
+  linkPinBtnEl = document.querySelector("#link-pin-btn");
+  linkDismissBtnEl = document.querySelector("#link-dismiss-btn");
   
   // Add event listeners
   startBtnEl?.addEventListener("click", startNode);
@@ -410,6 +564,9 @@ window.addEventListener("DOMContentLoaded", () => {
   logsBtnEl?.addEventListener("click", toggleLogs);
   openWebviewBtnEl?.addEventListener("click", openWebview);
   backBtnEl?.addEventListener("click", closeWebview);
+  linkFetchBtnEl?.addEventListener("click", fetchLink);
+  linkPinBtnEl?.addEventListener("click", pinLink);
+  linkDismissBtnEl?.addEventListener("click", dismissLink);
   closeLogsBtnEl?.addEventListener("click", toggleLogs);
   dbPathContainerEl?.addEventListener("click", selectCustomPath);
   logsOutputEl?.addEventListener("dblclick", clearLogs);